@@ -0,0 +1,171 @@
+use serde::{de, Deserialize, Deserializer};
+use std::fmt;
+
+use crate::rng::Rng;
+
+// --- COMBATE ---
+// Ataque corpo a corpo: rola d20 + attack contra a defense do alvo (que já
+// vem na escala de classe de armadura, 10-12 nos monstros). Se acertar, o dano
+// é a rolagem dos dados de dano (no mínimo 1).
+// resolve_attack não mexe em ninguém, só diz o que aconteceu; quem chama
+// aplica o dano. Com a mesma seed no Rng o resultado é sempre o mesmo.
+const HIT_DIE: u32 = 20;
+
+// --- Dados ---
+// Notação de RPG: 2d4+1 = dois dados de 4 lados, soma, mais 1.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Dice {
+    pub count: u32,
+    pub sides: u32,
+    pub bonus: i32,
+}
+
+impl Dice {
+    pub const fn new(count: u32, sides: u32, bonus: i32) -> Self {
+        Dice { count, sides, bonus }
+    }
+
+    // Lê "1d6", "2d4+1", "1d8-2". None se estiver mal formado.
+    pub fn parse(s: &str) -> Option<Self> {
+        let (count, rest) = s.split_once('d')?;
+        let (sides, bonus) = match rest.find(['+', '-']) {
+            Some(i) => (&rest[..i], rest[i..].parse().ok()?),
+            None => (rest, 0),
+        };
+        let dice = Dice::new(count.parse().ok()?, sides.parse().ok()?, bonus);
+        (dice.sides > 0).then_some(dice)
+    }
+
+    pub fn roll(&self, rng: &mut Rng) -> i32 {
+        let total: u32 = (0..self.count).map(|_| rng.range(1, self.sides as usize + 1) as u32).sum();
+        total as i32 + self.bonus
+    }
+}
+
+impl fmt::Display for Dice {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}d{}", self.count, self.sides)?;
+        if self.bonus != 0 {
+            write!(f, "{:+}", self.bonus)?;
+        }
+        Ok(())
+    }
+}
+
+// Nos arquivos de conteúdo os dados vêm como texto: "2d4+1"
+impl<'de> Deserialize<'de> for Dice {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let text = String::deserialize(deserializer)?;
+        Dice::parse(&text).ok_or_else(|| de::Error::custom(format!("dados inválidos: '{}' (esperava algo como 2d4+1)", text)))
+    }
+}
+
+// --- Stats ---
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Stats {
+    pub max_hp: i32,
+    pub hp: i32,
+    pub attack: i32,  // Bônus pra acertar
+    pub defense: i32, // Dificulta ser acertado
+    pub damage: Dice,
+}
+
+impl Stats {
+    // Vida cheia
+    pub const fn new(max_hp: i32, attack: i32, defense: i32, damage: Dice) -> Self {
+        Stats { max_hp, hp: max_hp, attack, defense, damage }
+    }
+
+    pub fn is_dead(&self) -> bool {
+        self.hp <= 0
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Attack {
+    Miss,
+    Hit { damage: i32, killed: bool },
+}
+
+pub fn resolve_attack(attacker: &Stats, defender: &Stats, rng: &mut Rng) -> Attack {
+    let to_hit = rng.range(1, HIT_DIE as usize + 1) as i32 + attacker.attack;
+    if to_hit < defender.defense {
+        return Attack::Miss;
+    }
+    let damage = attacker.damage.roll(rng).max(1);
+    Attack::Hit { damage, killed: damage >= defender.hp }
+}
+
+// Aplica o resultado de resolve_attack no alvo.
+pub fn apply_attack(defender: &mut Stats, attack: Attack) {
+    if let Attack::Hit { damage, .. } = attack {
+        defender.hp -= damage;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SEED: u64 = 42;
+
+    fn stats(hp: i32, attack: i32, defense: i32, damage: Dice) -> Stats {
+        Stats::new(hp, attack, defense, damage)
+    }
+
+    #[test]
+    fn same_seed_same_attack() {
+        let a = stats(10, 3, 12, Dice::new(1, 6, 0));
+        let d = stats(10, 0, 12, Dice::new(1, 6, 0));
+        let (mut r1, mut r2) = (Rng::new(SEED), Rng::new(SEED));
+        for _ in 0..50 {
+            assert_eq!(resolve_attack(&a, &d, &mut r1), resolve_attack(&a, &d, &mut r2));
+        }
+    }
+
+    #[test]
+    fn hit_and_miss() {
+        let mut rng = Rng::new(SEED);
+        // d20 + 0 contra defesa 1: sempre acerta. Contra 21: nunca.
+        let a = stats(10, 0, 10, Dice::new(1, 4, 0));
+        let easy = stats(10, 0, 1, Dice::new(1, 4, 0));
+        let hard = stats(10, 0, 21, Dice::new(1, 4, 0));
+        for _ in 0..100 {
+            assert!(matches!(resolve_attack(&a, &easy, &mut rng), Attack::Hit { .. }));
+            assert_eq!(resolve_attack(&a, &hard, &mut rng), Attack::Miss);
+        }
+    }
+
+    #[test]
+    fn hit_chance_with_game_stats() {
+        // Rato (ataque 0) contra o jogador (defesa 12): precisa de 12+ no d20, 45%
+        let rat = stats(4, 0, 10, Dice::new(1, 3, 0));
+        let player = stats(20, 3, 12, Dice::new(1, 6, 0));
+        let mut rng = Rng::new(SEED);
+        let hits = (0..10_000).filter(|_| resolve_attack(&rat, &player, &mut rng) != Attack::Miss).count();
+        assert!((4000..5000).contains(&hits), "{} acertos", hits);
+    }
+
+    #[test]
+    fn killed_when_damage_reaches_hp() {
+        let mut rng = Rng::new(SEED);
+        let a = stats(10, 100, 10, Dice::new(1, 4, 0));
+        let mut weak = stats(1, 0, 10, Dice::new(1, 4, 0));
+        let strong = stats(100, 0, 10, Dice::new(1, 4, 0));
+        let attack = resolve_attack(&a, &weak, &mut rng);
+        assert!(matches!(attack, Attack::Hit { killed: true, .. }));
+        apply_attack(&mut weak, attack);
+        assert!(weak.is_dead());
+        assert!(matches!(resolve_attack(&a, &strong, &mut rng), Attack::Hit { killed: false, .. }));
+    }
+
+    #[test]
+    fn minimum_damage_is_one() {
+        let mut rng = Rng::new(SEED);
+        let a = stats(10, 100, 10, Dice::new(1, 2, -5));
+        let d = stats(50, 0, 10, Dice::new(1, 4, 0));
+        for _ in 0..20 {
+            assert_eq!(resolve_attack(&a, &d, &mut rng), Attack::Hit { damage: 1, killed: false });
+        }
+    }
+}
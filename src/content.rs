@@ -0,0 +1,471 @@
+use macroquad::prelude::*;
+use ron::extensions::Extensions;
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use std::collections::HashSet;
+use std::fmt;
+use std::sync::OnceLock;
+
+use crate::combat::{Dice, Stats};
+use crate::equipment::{layer_source, Equip};
+use crate::item::{ItemEffect, ItemKind};
+use crate::loot::{Band, DepthRange, DepthTable, Table};
+use crate::monster::{Faction, MonsterKind};
+use crate::status::StatusEffect;
+use crate::TILE_SIZE;
+
+// --- CONTEÚDO ---
+// Os tipos de monstro e de item e as tabelas de spawn vêm de arquivos RON em
+// assets/ (o formato está comentado nos próprios arquivos). Carregam uma vez na
+// abertura, logo depois das texturas, e tudo é validado antes do jogo começar:
+// o erro diz o arquivo e a entrada com problema. Depois disso é só leitura
+// (monsters(), items(), ...), e os &'static MonsterKind / ItemKind apontam pra cá.
+pub const MONSTER_FILE: &str = "monsters.ron";
+pub const ITEM_FILE: &str = "items.ron";
+pub const SPAWN_FILE: &str = "spawns.ron";
+
+static MONSTERS: OnceLock<Vec<MonsterKind>> = OnceLock::new();
+static ITEMS: OnceLock<Vec<ItemKind>> = OnceLock::new();
+static SPAWNS: OnceLock<Spawns> = OnceLock::new();
+
+// Raridade vira peso no sorteio do gerador (andar sem tabela no spawns.ron)
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+pub enum Rarity {
+    Common,
+    Uncommon,
+    Rare,
+}
+
+impl Rarity {
+    pub fn weight(self) -> u32 {
+        match self {
+            Rarity::Common => 8,
+            Rarity::Uncommon => 3,
+            Rarity::Rare => 1,
+        }
+    }
+}
+
+// O que o gerador espalha em cada profundidade (ver loot.rs). Profundidade sem
+// faixa aqui sorteia pela raridade entre os tipos que cabem nela (depth/rarity).
+#[derive(Debug)]
+pub struct Spawns {
+    pub monsters: DepthTable<&'static MonsterKind>,
+    pub items: DepthTable<&'static ItemKind>,
+}
+
+// Tamanho (em pixels) das folhas de sprite, pra conferir se cada sprite cabe na sua
+pub struct Sheets {
+    pub monsters: Vec2,
+    pub items: Vec2,
+    pub equipment: Vec2,
+}
+
+// --- Erros ---
+#[derive(Debug)]
+pub enum ContentError {
+    Io { path: String, source: macroquad::Error },
+    Syntax { path: String, line: usize, column: usize, message: String }, // RON mal formado ou campo errado
+    Invalid { path: String, entry: String, message: String },            // Lido, mas não faz sentido
+}
+
+impl fmt::Display for ContentError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ContentError::Io { path, source } => write!(f, "não foi possível ler '{}': {}", path, source),
+            ContentError::Syntax { path, line, column, message } => write!(f, "{}: linha {}, coluna {}: {}", path, line, column, message),
+            ContentError::Invalid { path, entry, message } => write!(f, "{}: {}: {}", path, entry, message),
+        }
+    }
+}
+
+impl std::error::Error for ContentError {}
+
+// --- Entradas dos arquivos ---
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct MonsterDef {
+    name: String,
+    glyph: char,
+    sprite: (u32, u32), // Coluna e linha no monsters.png
+    hp: i32,
+    attack: i32,
+    defense: i32,
+    damage: Dice,
+    speed: i32,
+    ai: Faction,
+    #[serde(default)]
+    on_hit: Option<StatusEffect>,
+    #[serde(default)]
+    drops: Option<Table<char>>, // Glifos de items.ron
+    xp: u32,
+    depth: DepthRange,
+    rarity: Rarity,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ItemDef {
+    name: String,
+    glyph: char,
+    sprite: (u32, u32), // Coluna e linha no items.png
+    #[serde(default)]
+    stackable: bool,
+    weight: u32,
+    #[serde(default = "single")]
+    pile: u32,
+    #[serde(default)]
+    effect: Option<ItemEffect>,
+    #[serde(default)]
+    equip: Option<Equip>,
+    depth: DepthRange,
+    rarity: Rarity,
+}
+
+fn single() -> u32 {
+    1
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct SpawnsDef {
+    monsters: DepthTable<char>,
+    items: DepthTable<char>,
+}
+
+// --- Carga ---
+// Só chamar uma vez, na abertura. Itens primeiro: monstros e tabelas apontam pra eles.
+pub async fn load(sheets: &Sheets) -> Result<(), ContentError> {
+    let items = parse_items(ITEM_FILE, &read(ITEM_FILE).await?, sheets)?;
+    ITEMS.set(items).expect("conteúdo carregado duas vezes");
+    let monsters = parse_monsters(MONSTER_FILE, &read(MONSTER_FILE).await?, sheets.monsters, self::items())?;
+    MONSTERS.set(monsters).expect("conteúdo carregado duas vezes");
+    let spawns = parse_spawns(SPAWN_FILE, &read(SPAWN_FILE).await?, self::monsters(), self::items())?;
+    SPAWNS.set(spawns).expect("conteúdo carregado duas vezes");
+    Ok(())
+}
+
+pub fn monsters() -> &'static [MonsterKind] {
+    MONSTERS.get().expect("content::load roda na abertura")
+}
+
+pub fn items() -> &'static [ItemKind] {
+    ITEMS.get().expect("content::load roda na abertura")
+}
+
+pub fn spawns() -> &'static Spawns {
+    SPAWNS.get().expect("content::load roda na abertura")
+}
+
+async fn read(path: &str) -> Result<String, ContentError> {
+    load_string(path).await.map_err(|source| ContentError::Io { path: path.to_owned(), source })
+}
+
+// Option pode vir sem o Some(...)
+fn parse_ron<T: DeserializeOwned>(path: &str, text: &str) -> Result<T, ContentError> {
+    ron::Options::default()
+        .with_default_extension(Extensions::IMPLICIT_SOME)
+        .from_str(text)
+        .map_err(|e| ContentError::Syntax {
+            path: path.to_owned(),
+            line: e.position.line,
+            column: e.position.col,
+            message: e.code.to_string(),
+        })
+}
+
+fn parse_monsters(path: &str, text: &str, sheet: Vec2, items: &'static [ItemKind]) -> Result<Vec<MonsterKind>, ContentError> {
+    let defs: Vec<MonsterDef> = parse_ron(path, text)?;
+    let mut glyphs = HashSet::new();
+    let mut kinds = Vec::new();
+    for (index, def) in defs.into_iter().enumerate() {
+        let invalid = |message: String| ContentError::Invalid { path: path.to_owned(), entry: entry_label(index, &def.name), message };
+        let sprite = check_entry(&def.name, def.glyph, def.sprite, def.depth, sheet, &mut glyphs).map_err(invalid)?;
+        if def.hp <= 0 {
+            return Err(invalid(format!("vida deve ser maior que zero: {}", def.hp)));
+        }
+        if def.speed <= 0 {
+            return Err(invalid(format!("velocidade deve ser maior que zero: {}", def.speed)));
+        }
+        if let Some(effect) = def.on_hit {
+            check_effect(effect).map_err(invalid)?;
+        }
+        let drops = match def.drops {
+            Some(table) => Some(resolve_table(table, items, ITEM_FILE).map_err(|message| invalid(format!("drops: {}", message)))?),
+            None => None,
+        };
+        kinds.push(MonsterKind {
+            name: def.name.leak(),
+            glyph: def.glyph,
+            sprite,
+            stats: Stats::new(def.hp, def.attack, def.defense, def.damage),
+            speed: def.speed,
+            faction: def.ai,
+            on_hit: def.on_hit,
+            drops,
+            xp: def.xp,
+            depth: def.depth,
+            rarity: def.rarity,
+        });
+    }
+    Ok(kinds)
+}
+
+fn parse_items(path: &str, text: &str, sheets: &Sheets) -> Result<Vec<ItemKind>, ContentError> {
+    let defs: Vec<ItemDef> = parse_ron(path, text)?;
+    let mut glyphs = HashSet::new();
+    let mut kinds = Vec::new();
+    for (index, def) in defs.into_iter().enumerate() {
+        let invalid = |message: String| ContentError::Invalid { path: path.to_owned(), entry: entry_label(index, &def.name), message };
+        let sprite = check_entry(&def.name, def.glyph, def.sprite, def.depth, sheets.items, &mut glyphs).map_err(invalid)?;
+        if def.pile == 0 {
+            return Err(invalid("pile deve ser pelo menos 1".to_owned()));
+        }
+        if def.pile > 1 && !def.stackable {
+            return Err(invalid("só item empilhável aparece em monte (pile > 1)".to_owned()));
+        }
+        match def.effect {
+            Some(ItemEffect::Status(effect) | ItemEffect::StatusInView(effect)) => check_effect(effect).map_err(invalid)?,
+            Some(ItemEffect::Heal(_)) | None => {}
+        }
+        if let Some(equip) = &def.equip {
+            // Equipado ocupa o slot sozinho, não dá pra ser pilha
+            if def.stackable {
+                return Err(invalid("equipamento não pode ser empilhável".to_owned()));
+            }
+            if let Some(layer) = equip.layer
+                && !fits(layer_source(layer), sheets.equipment)
+            {
+                return Err(invalid(format!("camada {} fora do equipment.png", layer)));
+            }
+        }
+        kinds.push(ItemKind {
+            name: def.name.leak(),
+            glyph: def.glyph,
+            sprite,
+            stackable: def.stackable,
+            weight: def.weight,
+            pile: def.pile,
+            effect: def.effect,
+            equip: def.equip,
+            depth: def.depth,
+            rarity: def.rarity,
+        });
+    }
+    Ok(kinds)
+}
+
+fn parse_spawns(
+    path: &str,
+    text: &str,
+    monsters: &'static [MonsterKind],
+    items: &'static [ItemKind],
+) -> Result<Spawns, ContentError> {
+    let def: SpawnsDef = parse_ron(path, text)?;
+    Ok(Spawns {
+        monsters: resolve_bands(path, "monsters", def.monsters, monsters, MONSTER_FILE)?,
+        items: resolve_bands(path, "items", def.items, items, ITEM_FILE)?,
+    })
+}
+
+// Troca os glifos de cada faixa pelos tipos e confere as faixas
+fn resolve_bands<K: HasGlyph>(
+    path: &str,
+    list: &str,
+    table: DepthTable<char>,
+    kinds: &'static [K],
+    kind_file: &str,
+) -> Result<DepthTable<&'static K>, ContentError> {
+    let mut bands = Vec::new();
+    for (index, band) in table.bands.into_iter().enumerate() {
+        let invalid = |message: String| ContentError::Invalid {
+            path: path.to_owned(),
+            entry: format!("{}, faixa {} (andares {})", list, index + 1, band.depth),
+            message,
+        };
+        check_depth(band.depth).map_err(invalid)?;
+        if let Some(before) = bands.iter().position(|b: &Band<&K>| b.depth.overlaps(&band.depth)) {
+            return Err(invalid(format!("cruza com a faixa {} (andares {})", before + 1, bands[before].depth)));
+        }
+        let table = resolve_table(band.table, kinds, kind_file).map_err(invalid)?;
+        bands.push(Band { depth: band.depth, table });
+    }
+    Ok(DepthTable { bands })
+}
+
+fn resolve_table<K: HasGlyph>(table: Table<char>, kinds: &'static [K], kind_file: &str) -> Result<Table<&'static K>, String> {
+    table.check().map_err(|e| e.to_string())?;
+    table.resolve(&mut |glyph| {
+        kinds
+            .iter()
+            .find(|kind| kind.glyph() == glyph)
+            .ok_or_else(|| format!("glifo '{}' não existe em {}", glyph, kind_file))
+    })
+}
+
+// Monstro e item, pra achar pelo glifo nas tabelas
+trait HasGlyph {
+    fn glyph(&self) -> char;
+}
+
+impl HasGlyph for MonsterKind {
+    fn glyph(&self) -> char {
+        self.glyph
+    }
+}
+
+impl HasGlyph for ItemKind {
+    fn glyph(&self) -> char {
+        self.glyph
+    }
+}
+
+// "entrada 3 (Goblin)". Número humano, começa em 1.
+fn entry_label(index: usize, name: &str) -> String {
+    if name.is_empty() {
+        format!("entrada {}", index + 1)
+    } else {
+        format!("entrada {} ({})", index + 1, name)
+    }
+}
+
+// O que monstro e item conferem igual. Devolve o pedaço da folha com o sprite.
+fn check_entry(
+    name: &str,
+    glyph: char,
+    (col, row): (u32, u32),
+    depth: DepthRange,
+    sheet: Vec2,
+    glyphs: &mut HashSet<char>,
+) -> Result<Rect, String> {
+    if name.trim().is_empty() {
+        return Err("nome vazio".to_owned());
+    }
+    if glyph.is_whitespace() {
+        return Err("glifo não pode ser espaço".to_owned());
+    }
+    if !glyphs.insert(glyph) {
+        return Err(format!("glifo '{}' já usado por outra entrada", glyph));
+    }
+    let sprite = Rect::new(col as f32 * TILE_SIZE, row as f32 * TILE_SIZE, TILE_SIZE, TILE_SIZE);
+    if !fits(sprite, sheet) {
+        return Err(format!("sprite ({}, {}) fora da folha ({}x{} quadros)", col, row, (sheet.x / TILE_SIZE) as u32, (sheet.y / TILE_SIZE) as u32));
+    }
+    check_depth(depth)?;
+    Ok(sprite)
+}
+
+fn check_depth(depth: DepthRange) -> Result<(), String> {
+    if depth.min == 0 {
+        return Err("depth.min começa em 1 (primeiro andar)".to_owned());
+    }
+    if let Some(max) = depth.max
+        && max < depth.min
+    {
+        return Err(format!("depth.max ({}) menor que depth.min ({})", max, depth.min));
+    }
+    Ok(())
+}
+
+fn check_effect(effect: StatusEffect) -> Result<(), String> {
+    if effect.turns == 0 {
+        return Err(format!("efeito {} com 0 turnos", effect.kind.name()));
+    }
+    // Sem potency o veneno/regeneração passa em silêncio, sem mexer na vida
+    if effect.kind.uses_potency() && effect.potency <= 0 {
+        return Err(format!("efeito {} precisa de potency maior que zero: {}", effect.kind.name(), effect.potency));
+    }
+    Ok(())
+}
+
+fn fits(rect: Rect, sheet: Vec2) -> bool {
+    rect.right() <= sheet.x && rect.bottom() <= sheet.y
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    // Folhas grandes o bastante pra qualquer sprite dos arquivos
+    const SHEET: Vec2 = vec2(TILE_SIZE * 32.0, TILE_SIZE * 32.0);
+    const SHEETS: Sheets = Sheets { monsters: SHEET, items: SHEET, equipment: SHEET };
+
+    fn asset(file: &str) -> String {
+        std::fs::read_to_string(format!("assets/{}", file)).unwrap()
+    }
+
+    // Carrega os arquivos de assets/ nos OnceLock, igual ao load do jogo mas sem
+    // macroquad. Serve pros testes de outros módulos que precisam dos tipos
+    // (saves, por exemplo). Pode chamar de vários testes, carrega uma vez só.
+    pub(crate) fn load_shipped() {
+        ITEMS.get_or_init(|| parse_items(ITEM_FILE, &asset(ITEM_FILE), &SHEETS).unwrap());
+        MONSTERS.get_or_init(|| parse_monsters(MONSTER_FILE, &asset(MONSTER_FILE), SHEETS.monsters, items()).unwrap());
+        SPAWNS.get_or_init(|| parse_spawns(SPAWN_FILE, &asset(SPAWN_FILE), monsters(), items()).unwrap());
+    }
+
+    fn spawns_error(text: &str) -> String {
+        load_shipped();
+        parse_spawns(SPAWN_FILE, text, monsters(), items()).unwrap_err().to_string()
+    }
+
+    #[test]
+    fn shipped_content_is_valid() {
+        load_shipped();
+        assert!(!monsters().is_empty() && !items().is_empty());
+        assert!(spawns().monsters.get(1).is_some() && spawns().items.get(1).is_some());
+    }
+
+    #[test]
+    fn entries_point_to_the_bad_one() {
+        let text = asset(ITEM_FILE).replace("glyph: '$'", "glyph: '!'");
+        let error = parse_items(ITEM_FILE, &text, &SHEETS).unwrap_err().to_string();
+        assert_eq!(error, "items.ron: entrada 2 (Moeda de ouro): glifo '!' já usado por outra entrada");
+
+        let text = asset(MONSTER_FILE).replace("name: \"Rato\",", "name: \"Rato\", voa: true,");
+        load_shipped();
+        let error = parse_monsters(MONSTER_FILE, &text, SHEETS.monsters, items()).unwrap_err();
+        assert!(matches!(error, ContentError::Syntax { .. }), "{}", error);
+    }
+
+    #[test]
+    fn effects_need_potency() {
+        load_shipped();
+        let text = asset(MONSTER_FILE).replace("(kind: Poison, turns: 3, potency: 1)", "(kind: Poison, turns: 3)");
+        let error = parse_monsters(MONSTER_FILE, &text, SHEETS.monsters, items()).unwrap_err().to_string();
+        assert_eq!(error, "monsters.ron: entrada 3 (Goblin): efeito poison precisa de potency maior que zero: 0");
+
+        let text = asset(ITEM_FILE).replace("(kind: Regeneration, turns: 10, potency: 1)", "(kind: Regeneration, turns: 10, potency: -2)");
+        let error = parse_items(ITEM_FILE, &text, &SHEETS).unwrap_err().to_string();
+        assert!(error.ends_with("efeito regeneration precisa de potency maior que zero: -2"), "{}", error);
+    }
+
+    #[test]
+    fn spawns_reject_unknown_glyph() {
+        let error = spawns_error("(monsters: [(depth: (min: 1), table: (entries: [(1, One('Z'))]))], items: [])");
+        assert_eq!(error, "spawns.ron: monsters, faixa 1 (andares 1+): glifo 'Z' não existe em monsters.ron");
+    }
+
+    #[test]
+    fn spawns_reject_bad_tables() {
+        let error = spawns_error("(monsters: [], items: [(depth: (min: 2, max: 3), table: (entries: [(0, One('!'))]))])");
+        assert_eq!(error, "spawns.ron: items, faixa 1 (andares 2-3): os pesos das entries somam zero");
+        let error = spawns_error("(monsters: [], items: [(depth: (min: 1), table: ())])");
+        assert_eq!(error, "spawns.ron: items, faixa 1 (andares 1+): tabela vazia (sem always nem entries)");
+    }
+
+    #[test]
+    fn spawns_reject_overlapping_bands() {
+        let error = spawns_error(
+            "(monsters: [(depth: (min: 1, max: 3), table: (always: [One('r')])), (depth: (min: 3), table: (always: [One('g')]))], items: [])",
+        );
+        assert_eq!(error, "spawns.ron: monsters, faixa 2 (andares 3+): cruza com a faixa 1 (andares 1-3)");
+    }
+
+    #[test]
+    fn drops_reject_unknown_glyph() {
+        load_shipped();
+        let text = asset(MONSTER_FILE).replace("One('$')", "One('Z')");
+        let error = parse_monsters(MONSTER_FILE, &text, SHEETS.monsters, items()).unwrap_err().to_string();
+        assert_eq!(error, "monsters.ron: entrada 3 (Goblin): drops: glifo 'Z' não existe em items.ron");
+    }
+}
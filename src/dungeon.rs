@@ -0,0 +1,176 @@
+use crate::fov::{self, TileMask};
+use crate::item::{self, FloorItem, ItemStack};
+use crate::map::{Map, MapError};
+use crate::mapgen::{LayoutTable, FLOOR, SPAWN, STAIRS_DOWN, STAIRS_UP};
+use crate::monster::{self, Monster, MonsterKind};
+use crate::tiles::TileRegistry;
+
+// --- MASMORRA ---
+// Vários andares empilhados. Cada andar é gerado na primeira visita e
+// fica guardado, então voltar pra um andar já visitado reencontra ele igual.
+//
+// Convenção dos mapas (gerados ou feitos à mão):
+//   's' = entrada do andar, '>' = saída pra baixo.
+// No primeiro andar a entrada continua sendo spawn. Nos outros ela vira
+// '<' (escada pra cima). No último andar o '>' vira chão.
+pub struct Floor {
+    pub depth: u32, // 1 = primeiro andar
+    pub map: Map,
+    pub visible: TileMask,  // O que o jogador enxerga agora
+    pub explored: TileMask, // Tudo que o jogador já viu nesse andar
+    pub monsters: Vec<Monster>, // Ficam no andar, congelados enquanto o jogador está em outro
+    pub corpses: Vec<Corpse>,   // O que sobrou dos monstros mortos
+    pub items: Vec<FloorItem>,  // Itens no chão. Vários no mesmo tile = pilha, o último fica por cima
+}
+
+// Só decoração por enquanto: o sprite do monstro, apagado, no tile onde morreu.
+pub struct Corpse {
+    pub col: usize,
+    pub row: usize,
+    pub kind: &'static MonsterKind,
+}
+
+impl Floor {
+    // Tem algum monstro no tile (col, row)?
+    pub fn monster_at(&self, col: usize, row: usize) -> Option<&Monster> {
+        self.monsters.iter().find(|m| (m.col, m.row) == (col, row))
+    }
+
+    // Índice em items do item de cima no tile (col, row)
+    pub fn item_at(&self, col: usize, row: usize) -> Option<usize> {
+        self.items.iter().rposition(|i| (i.col, i.row) == (col, row))
+    }
+
+    // Põe a pilha no tile. Em cima de uma do mesmo tipo empilhável, junta com ela.
+    pub fn place_item(&mut self, col: usize, row: usize, stack: ItemStack) {
+        let existing = self
+            .items
+            .iter_mut()
+            .find(|i| (i.col, i.row) == (col, row) && stack.kind.stackable && std::ptr::eq(i.stack.kind, stack.kind));
+        match existing {
+            Some(item) => item.stack.count += stack.count,
+            None => self.items.push(FloorItem { col, row, stack }),
+        }
+    }
+}
+
+pub struct Dungeon {
+    pub seed: u64,
+    floors: Vec<Floor>, // floors[0] = profundidade 1, cresce conforme o jogador desce
+    current: usize,     // Índice do andar ativo em floors
+    max_depth: u32,
+}
+
+impl Dungeon {
+    // Cria a masmorra já com o primeiro andar pronto.
+    pub async fn new(seed: u64, layouts: &LayoutTable, registry: &TileRegistry) -> Result<Self, MapError> {
+        let max_depth = layouts.max_depth();
+        let first = build_floor(seed, 1, max_depth, layouts, registry).await?;
+        Ok(Dungeon {
+            seed,
+            floors: vec![first],
+            current: 0,
+            max_depth,
+        })
+    }
+
+    // Masmorra de um save: andares já prontos, jogador no andar depth.
+    pub fn restore(seed: u64, floors: Vec<Floor>, depth: u32, max_depth: u32) -> Self {
+        assert!(depth >= 1 && depth as usize <= floors.len(), "andar atual precisa existir");
+        Dungeon {
+            seed,
+            floors,
+            current: (depth - 1) as usize,
+            max_depth,
+        }
+    }
+
+    // Todos os andares já gerados, do primeiro pro mais fundo
+    pub fn floors(&self) -> &[Floor] {
+        &self.floors
+    }
+
+    pub fn floor(&self) -> &Floor {
+        &self.floors[self.current]
+    }
+
+    pub fn floor_mut(&mut self) -> &mut Floor {
+        &mut self.floors[self.current]
+    }
+
+    pub fn depth(&self) -> u32 {
+        self.floor().depth
+    }
+
+    pub fn max_depth(&self) -> u32 {
+        self.max_depth
+    }
+
+    // Recalcula o que o jogador vê do tile origin e guarda na memória do andar.
+    pub fn update_view(&mut self, origin: (usize, usize), radius: usize, registry: &TileRegistry) {
+        let floor = &mut self.floors[self.current];
+        let map = &floor.map;
+        floor.visible = fov::compute_fov(map.width, map.height, origin, radius, |col, row| {
+            registry.is_opaque(map.tile(col as isize, row as isize))
+        });
+        floor.explored.union_with(&floor.visible);
+    }
+
+    // Troca o andar ativo pra profundidade vizinha, gerando se ainda não existir.
+    pub async fn go_to(&mut self, depth: u32, layouts: &LayoutTable, registry: &TileRegistry) -> Result<&Floor, MapError> {
+        if depth == 0 || depth > self.max_depth {
+            return Err(MapError::NoLayout(depth));
+        }
+
+        let index = (depth - 1) as usize;
+        // Andares são gerados em ordem, então só pode faltar o próximo
+        if index == self.floors.len() {
+            let floor = build_floor(self.seed, depth, self.max_depth, layouts, registry).await?;
+            self.floors.push(floor);
+        }
+        self.current = index;
+        Ok(self.floor())
+    }
+}
+
+// Seed de cada andar derivada da seed da masmorra. Mesma seed => mesmos andares.
+fn floor_seed(seed: u64, depth: u32) -> u64 {
+    seed ^ (depth as u64).wrapping_mul(0x9E37_79B9_7F4A_7C15)
+}
+
+async fn build_floor(
+    seed: u64,
+    depth: u32,
+    max_depth: u32,
+    layouts: &LayoutTable,
+    registry: &TileRegistry,
+) -> Result<Floor, MapError> {
+    // A LayoutTable é contínua até max_depth e o read_run recusa saves mais fundos
+    // que ela, mas se faltar layout vira erro em vez de derrubar o jogo
+    let layout = layouts.layout_for(depth).ok_or(MapError::NoLayout(depth))?;
+    let mut map = layout.build(floor_seed(seed, depth), registry).await?;
+
+    if depth > 1 {
+        map.replace(SPAWN, STAIRS_UP);
+    }
+    if depth == max_depth {
+        map.replace(STAIRS_DOWN, FLOOR);
+    } else if map.find(STAIRS_DOWN).is_none() {
+        // Mapa feito à mão sem saída no meio da masmorra: ficaria preso.
+        return Err(MapError::NoStairsDown);
+    }
+
+    let monsters = monster::spawn_monsters(&mut map, depth, floor_seed(seed, depth), registry);
+    let items = item::spawn_items(&map, depth, floor_seed(seed, depth));
+    let visible = TileMask::new(map.width, map.height);
+    let explored = TileMask::new(map.width, map.height);
+    Ok(Floor {
+        depth,
+        map,
+        visible,
+        explored,
+        monsters,
+        corpses: Vec::new(),
+        items,
+    })
+}
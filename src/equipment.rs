@@ -0,0 +1,189 @@
+use macroquad::prelude::*;
+use serde::Deserialize;
+
+use crate::combat::Stats;
+use crate::item::ItemStack;
+use crate::TILE_SIZE;
+
+// --- EQUIPAMENTO ---
+// Quatro slots no jogador, um item em cada. Item equipado sai do inventário
+// (e não conta no peso dele) e soma seu bônus nos atributos do jogador.
+// Os atributos base continuam no Player; o valor que vale no jogo é sempre
+// base + bonus() de tudo que está equipado (ver Player::effective_stats).
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+pub enum EquipSlot {
+    Weapon,
+    Armor,
+    Ring,
+    Amulet,
+}
+
+impl EquipSlot {
+    // Ordem no inventário e no array de slots
+    pub const ALL: [EquipSlot; 4] = [EquipSlot::Weapon, EquipSlot::Armor, EquipSlot::Ring, EquipSlot::Amulet];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            EquipSlot::Weapon => "Arma",
+            EquipSlot::Armor => "Armadura",
+            EquipSlot::Ring => "Anel",
+            EquipSlot::Amulet => "Amuleto",
+        }
+    }
+
+    fn index(self) -> usize {
+        EquipSlot::ALL.iter().position(|&slot| slot == self).expect("ALL tem todos os slots")
+    }
+}
+
+// O que um equipamento muda. Pode ser negativo (armadura pesada que deixa lento...).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Bonus {
+    pub attack: i32,
+    pub defense: i32,
+    pub fov: i32,   // Tiles a mais no raio de visão
+    pub speed: i32, // Somado na velocidade (NORMAL_SPEED = 10)
+}
+
+impl Bonus {
+    pub const fn new(attack: i32, defense: i32, fov: i32, speed: i32) -> Self {
+        Bonus { attack, defense, fov, speed }
+    }
+
+    fn add(self, other: Bonus) -> Bonus {
+        Bonus::new(
+            self.attack + other.attack,
+            self.defense + other.defense,
+            self.fov + other.fov,
+            self.speed + other.speed,
+        )
+    }
+
+    // "+2 ataque, -1 velocidade". Vazio se não muda nada.
+    pub fn describe(&self) -> String {
+        [(self.attack, "ataque"), (self.defense, "defesa"), (self.fov, "visão"), (self.speed, "velocidade")]
+            .iter()
+            .filter(|(value, _)| *value != 0)
+            .map(|(value, name)| format!("{:+} {}", value, name))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    // Stats de combate com o bônus somado. Vida e dano ficam iguais.
+    pub fn apply(&self, stats: &Stats) -> Stats {
+        Stats {
+            attack: stats.attack + self.attack,
+            defense: stats.defense + self.defense,
+            ..*stats
+        }
+    }
+}
+
+// Tipo de item que dá pra equipar
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Equip {
+    pub slot: EquipSlot,
+    #[serde(default)]
+    pub bonus: Bonus,
+    #[serde(default)]
+    pub layer: Option<usize>, // Quadro do equipment.png desenhado por cima do jogador. None = não aparece
+}
+
+#[derive(Default)]
+pub struct Equipment {
+    slots: [Option<ItemStack>; 4], // Na ordem de EquipSlot::ALL
+}
+
+impl Equipment {
+    pub fn get(&self, slot: EquipSlot) -> Option<&ItemStack> {
+        self.slots[slot.index()].as_ref()
+    }
+
+    // Tudo que está equipado, na ordem dos slots (também a ordem de desenho)
+    pub fn worn(&self) -> impl Iterator<Item = &ItemStack> {
+        self.slots.iter().flatten()
+    }
+
+    // Põe o item no slot dele e devolve o que estava lá.
+    // Só chamar com item equipável (kind.equip = Some).
+    pub fn equip(&mut self, stack: ItemStack) -> Option<ItemStack> {
+        let equip = stack.kind.equip.as_ref().expect("só item equipável vai pro slot");
+        self.slots[equip.slot.index()].replace(stack)
+    }
+
+    pub fn unequip(&mut self, slot: EquipSlot) -> Option<ItemStack> {
+        self.slots[slot.index()].take()
+    }
+
+    // Soma dos bônus de tudo que está equipado
+    pub fn bonus(&self) -> Bonus {
+        self.worn()
+            .filter_map(|stack| stack.kind.equip.as_ref())
+            .fold(Bonus::default(), |total, equip| total.add(equip.bonus))
+    }
+}
+
+// Pedaço do equipment.png com a camada, do tamanho do sprite do jogador
+pub fn layer_source(layer: usize) -> Rect {
+    Rect::new(layer as f32 * TILE_SIZE, 0.0, TILE_SIZE, TILE_SIZE)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::content;
+    use crate::item;
+    use crate::player::Player;
+    use crate::turn::NORMAL_SPEED;
+
+    fn stack(glyph: char) -> ItemStack {
+        content::tests::load_shipped();
+        ItemStack::new(item::kind_for_glyph(glyph).unwrap(), 1)
+    }
+
+    #[test]
+    fn equip_swaps_out_what_was_in_the_slot() {
+        let mut equipment = Equipment::default();
+        assert!(equipment.equip(stack(')')).is_none());
+        assert!(equipment.equip(stack('[')).is_none());
+        let old = equipment.equip(stack(')')).unwrap();
+        assert_eq!(old.kind.name, "Adaga");
+        assert_eq!(equipment.worn().count(), 2);
+
+        assert_eq!(equipment.unequip(EquipSlot::Armor).map(|s| s.kind.name), Some("Armadura de couro"));
+        assert!(equipment.unequip(EquipSlot::Armor).is_none());
+        assert!(equipment.get(EquipSlot::Weapon).is_some());
+    }
+
+    #[test]
+    fn player_stats_add_everything_worn() {
+        let mut player = Player::new(0, 0);
+        let base = player.stats;
+        for glyph in [')', '[', '=', '"'] {
+            player.equipment.equip(stack(glyph));
+        }
+        player.refresh_speed();
+        assert_eq!(player.equipment.bonus(), Bonus::new(2, 2, 2, 5));
+        let stats = player.effective_stats();
+        assert_eq!((stats.attack, stats.defense), (base.attack + 2, base.defense + 2));
+        assert_eq!((stats.hp, stats.damage), (base.hp, base.damage));
+        assert_eq!(player.fov(), player.fov_radius + 2);
+        assert_eq!(player.energy.speed, NORMAL_SPEED + 5);
+
+        // Tirando tudo volta ao base
+        for slot in EquipSlot::ALL {
+            player.equipment.unequip(slot);
+        }
+        player.refresh_speed();
+        assert_eq!((player.effective_stats().attack, player.fov()), (base.attack, player.fov_radius));
+        assert_eq!(player.energy.speed, NORMAL_SPEED);
+    }
+
+    #[test]
+    fn bonus_describes_only_what_changes() {
+        assert_eq!(Bonus::new(2, 0, 0, -1).describe(), "+2 ataque, -1 velocidade");
+        assert_eq!(Bonus::default().describe(), "");
+    }
+}
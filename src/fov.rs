@@ -0,0 +1,249 @@
+// --- CAMPO DE VISÃO ---
+// Shadowcasting recursivo (Björn Bergström, roguebasin).
+// O mapa é dividido em 8 octantes e cada um é varrido linha por linha a
+// partir da origem; tiles opacos projetam "sombras" (faixas de inclinação)
+// que as linhas seguintes não enxergam.
+//
+// Não depende de Map nem do macroquad: recebe só o tamanho da grade e uma
+// função dizendo se (col, row) bloqueia a visão.
+
+// --- TileMask ---
+// Bitset width * height, um bit por tile. Usado pros tiles visíveis
+// e pra memória de tiles já explorados de cada andar.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TileMask {
+    width: usize,
+    height: usize,
+    words: Vec<u64>,
+}
+
+impl TileMask {
+    pub fn new(width: usize, height: usize) -> Self {
+        TileMask {
+            width,
+            height,
+            words: vec![0; (width * height).div_ceil(64)],
+        }
+    }
+
+    pub fn get(&self, col: usize, row: usize) -> bool {
+        if col >= self.width || row >= self.height {
+            return false;
+        }
+        let idx = row * self.width + col;
+        self.words[idx / 64] & (1 << (idx % 64)) != 0
+    }
+
+    pub fn set(&mut self, col: usize, row: usize) {
+        if col < self.width && row < self.height {
+            let idx = row * self.width + col;
+            self.words[idx / 64] |= 1 << (idx % 64);
+        }
+    }
+
+    // Liga todos os bits que estão ligados em other (mesmo tamanho).
+    pub fn union_with(&mut self, other: &TileMask) {
+        debug_assert_eq!((self.width, self.height), (other.width, other.height));
+        for (word, other_word) in self.words.iter_mut().zip(&other.words) {
+            *word |= other_word;
+        }
+    }
+}
+
+// Multiplicadores que levam o octante 0 pra cada um dos 8 octantes.
+const OCTANTS: [(i32, i32, i32, i32); 8] = [
+    (1, 0, 0, 1),
+    (0, 1, 1, 0),
+    (0, -1, 1, 0),
+    (-1, 0, 0, 1),
+    (-1, 0, 0, -1),
+    (0, -1, -1, 0),
+    (0, 1, -1, 0),
+    (1, 0, 0, -1),
+];
+
+// Tudo que é visível a partir de origin até radius tiles (distância euclidiana).
+// A origem sempre é visível. Fora da grade conta como opaco.
+pub fn compute_fov(
+    width: usize,
+    height: usize,
+    origin: (usize, usize),
+    radius: usize,
+    is_opaque: impl Fn(usize, usize) -> bool,
+) -> TileMask {
+    let mut visible = TileMask::new(width, height);
+    visible.set(origin.0, origin.1);
+
+    let caster = Caster {
+        width: width as i32,
+        height: height as i32,
+        origin: (origin.0 as i32, origin.1 as i32),
+        radius: radius as i32,
+        is_opaque: &is_opaque,
+    };
+    for octant in OCTANTS {
+        caster.cast(&mut visible, 1, 1.0, 0.0, octant);
+    }
+    visible
+}
+
+struct Caster<'a> {
+    width: i32,
+    height: i32,
+    origin: (i32, i32),
+    radius: i32,
+    is_opaque: &'a dyn Fn(usize, usize) -> bool,
+}
+
+impl Caster<'_> {
+    fn in_bounds(&self, col: i32, row: i32) -> bool {
+        col >= 0 && row >= 0 && col < self.width && row < self.height
+    }
+
+    fn blocks(&self, col: i32, row: i32) -> bool {
+        !self.in_bounds(col, row) || (self.is_opaque)(col as usize, row as usize)
+    }
+
+    // Varre o octante a partir da linha `row`, só entre as inclinações start e end.
+    fn cast(&self, visible: &mut TileMask, row: i32, mut start: f32, end: f32, octant: (i32, i32, i32, i32)) {
+        if start < end {
+            return;
+        }
+        let (xx, xy, yx, yy) = octant;
+        let radius_sq = self.radius * self.radius;
+        let mut new_start = 0.0;
+
+        for distance in row..=self.radius {
+            let dy = -distance;
+            let mut blocked = false;
+
+            for dx in -distance..=0 {
+                let col = self.origin.0 + dx * xx + dy * xy;
+                let row = self.origin.1 + dx * yx + dy * yy;
+                let left_slope = (dx as f32 - 0.5) / (dy as f32 + 0.5);
+                let right_slope = (dx as f32 + 0.5) / (dy as f32 - 0.5);
+
+                if start < right_slope {
+                    continue;
+                } else if end > left_slope {
+                    break;
+                }
+
+                if dx * dx + dy * dy <= radius_sq && self.in_bounds(col, row) {
+                    visible.set(col as usize, row as usize);
+                }
+
+                if blocked {
+                    // Ainda dentro da sombra de um bloco
+                    if self.blocks(col, row) {
+                        new_start = right_slope;
+                    } else {
+                        blocked = false;
+                        start = new_start;
+                    }
+                } else if self.blocks(col, row) && distance < self.radius {
+                    // Começo de um bloco: varre o que sobrou antes dele na próxima linha
+                    blocked = true;
+                    self.cast(visible, distance + 1, start, left_slope, octant);
+                    new_start = right_slope;
+                }
+            }
+
+            if blocked {
+                break;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Mapa à mão: '#' bloqueia a visão, '@' é a origem. Devolve o mesmo mapa
+    // só com os tiles visíveis (o resto vira espaço).
+    fn look(rows: &[&str], radius: usize) -> Vec<String> {
+        let grid: Vec<Vec<char>> = rows.iter().map(|row| row.chars().collect()).collect();
+        let (width, height) = (grid[0].len(), grid.len());
+        let origin = (0..height)
+            .flat_map(|row| (0..width).map(move |col| (col, row)))
+            .find(|&(col, row)| grid[row][col] == '@')
+            .expect("mapa sem @");
+        let visible = compute_fov(width, height, origin, radius, |col, row| grid[row][col] == '#');
+        (0..height)
+            .map(|row| (0..width).map(|col| if visible.get(col, row) { grid[row][col] } else { ' ' }).collect())
+            .collect()
+    }
+
+    #[test]
+    fn open_room_sees_everything() {
+        let room = ["#######", "#.....#", "#.....#", "#..@..#", "#.....#", "#.....#", "#######"];
+        assert_eq!(look(&room, 10), room);
+    }
+
+    #[test]
+    fn pillar_casts_a_shadow() {
+        let room = [
+            "###########",
+            "#.........#",
+            "#.........#",
+            "#.@.#.....#",
+            "#.........#",
+            "#.........#",
+            "###########",
+        ];
+        let expected = [
+            "###########",
+            "#.........#",
+            "#.......   ",
+            "#.@.#      ",
+            "#.......   ",
+            "#.........#",
+            "###########",
+        ];
+        assert_eq!(look(&room, 20), expected);
+    }
+
+    #[test]
+    fn wall_hides_the_other_side() {
+        let rooms = ["#########", "#...#...#", "#...#...#", "#.@.#...#", "#...#...#", "#########"];
+        let expected = ["#####    ", "#...#    ", "#...#    ", "#.@.#    ", "#...#    ", "#####    "];
+        assert_eq!(look(&rooms, 20), expected);
+    }
+
+    #[test]
+    fn radius_limits_the_view() {
+        let open = [
+            ".........",
+            ".........",
+            ".........",
+            ".........",
+            "....@....",
+            ".........",
+            ".........",
+            ".........",
+            ".........",
+        ];
+        // Distância euclidiana: (3, 0) entra, (2, 3) e (3, 3) não
+        let expected = [
+            "         ",
+            "    .    ",
+            "  .....  ",
+            "  .....  ",
+            " ...@... ",
+            "  .....  ",
+            "  .....  ",
+            "    .    ",
+            "         ",
+        ];
+        assert_eq!(look(&open, 3), expected);
+        assert_eq!(look(&open, 0).concat().trim(), "@");
+    }
+
+    #[test]
+    fn origin_on_the_edge() {
+        // Fora da grade conta como opaco, não pode dar pânico
+        let corner = ["@..", "...", "..."];
+        assert_eq!(look(&corner, 5), corner);
+    }
+}
@@ -0,0 +1,704 @@
+use crate::combat::{self, Attack, Stats};
+use crate::dungeon::{Corpse, Dungeon, Floor};
+use crate::equipment::{Bonus, EquipSlot};
+use crate::item::{self, ItemEffect, ItemStack};
+use crate::level::Perk;
+use crate::map::Map;
+use crate::monster::{Action, Faction, Monster};
+use crate::path::{self, Connectivity, DijkstraMap};
+use crate::player::Player;
+use crate::rng::Rng;
+use crate::status::{StatusKind, Statuses, CONFUSION_CHANCE};
+use crate::tiles::{CornerRule, TileFlag, TileRegistry};
+use crate::turn::{Command, Scheduler, ACTION_COST};
+
+// --- RUN ---
+// Tudo que existe só durante um jogo, do "Novo Jogo" até voltar pro menu.
+pub struct Run {
+    pub dungeon: Dungeon,
+    pub player: Player,
+    pub scheduler: Scheduler,
+    pub rng: Rng, // Sorteios durante o jogo (IA, combate), derivado da seed da masmorra
+    pub log: Vec<String>, // Mensagens pro jogador, a mais nova no fim
+    pub slot: usize,      // Slot de save desse jogo (1 a save::SLOT_COUNT)
+    route: Vec<(usize, usize)>, // Passos que faltam da viagem (clique no mapa), o próximo no fim
+    exploring: bool,            // Auto-explorar ligado: a rota é refeita a cada passo
+}
+
+// Quantas mensagens o log guarda
+const LOG_SIZE: usize = 50;
+
+impl Run {
+    pub fn new(dungeon: Dungeon, player: Player, slot: usize) -> Self {
+        let rng = Rng::new(dungeon.seed.rotate_left(32));
+        Run {
+            dungeon,
+            player,
+            scheduler: Scheduler::default(),
+            rng,
+            log: Vec::new(),
+            slot,
+            route: Vec::new(),
+            exploring: false,
+        }
+    }
+
+    // Resolve o comando do jogador e passa o tempo até ele poder agir de novo.
+    // Retorna false se nada aconteceu (andar contra um muro não gasta turno).
+    pub fn perform(&mut self, command: Command, registry: &TileRegistry) -> bool {
+        let cost = match command {
+            Command::Move { dx, dy } => {
+                let floor = self.dungeon.floor();
+                let from = (self.player.col as isize, self.player.row as isize);
+                let col = from.0 + dx;
+                let row = from.1 + dy;
+                if !can_enter(&floor.map, registry, from, (col, row)) {
+                    return false;
+                }
+                // Monstro no caminho: andar pra cima dele é atacar
+                if floor.monster_at(col as usize, row as usize).is_some() {
+                    self.player_attack(col as usize, row as usize);
+                } else {
+                    self.player.step_to(col as usize, row as usize);
+                    self.describe_items_here();
+                }
+                ACTION_COST
+            }
+            Command::Wait => ACTION_COST,
+            Command::Pickup => {
+                if !self.pickup() {
+                    return false;
+                }
+                ACTION_COST
+            }
+            Command::Use { index } => {
+                if !self.use_item(index) {
+                    return false;
+                }
+                ACTION_COST
+            }
+            Command::Drop { index } => {
+                if !self.drop_item(index) {
+                    return false;
+                }
+                ACTION_COST
+            }
+            Command::Equip { index } => {
+                if !self.equip_item(index) {
+                    return false;
+                }
+                ACTION_COST
+            }
+            Command::Unequip { slot } => {
+                if !self.unequip_item(slot) {
+                    return false;
+                }
+                ACTION_COST
+            }
+        };
+
+        self.player.energy.spend(cost);
+
+        // Os monstros decidem vendo o jogador já na posição nova
+        let origin = self.player.tile();
+        self.dungeon.update_view(origin, self.player.fov(), registry);
+
+        let floor = self.dungeon.floor_mut();
+        let mut target = Target {
+            tile: origin,
+            stats: &mut self.player.stats,
+            gear: self.player.equipment.bonus(),
+            status: &mut self.player.status,
+        };
+        let rng = &mut self.rng;
+        let log = &mut self.log;
+        let mut xp = 0; // Monstros que morreram de veneno no caminho
+        self.scheduler.advance(&mut self.player.energy, |new_turn| {
+            if new_turn {
+                xp += status_turn(floor, &mut target, rng, log);
+            }
+            monsters_tick(floor, &mut target, registry, rng, log)
+        });
+        self.award_xp(xp);
+        // Pressa ou lentidão que acabou no meio da espera vale a partir da próxima ação
+        self.player.refresh_speed();
+        if self.log.len() > LOG_SIZE {
+            self.log.drain(..self.log.len() - LOG_SIZE);
+        }
+        true
+    }
+
+    // Jogador ataca o monstro em (col, row). Morto, vira cadáver no chão (ver bury).
+    fn player_attack(&mut self, col: usize, row: usize) {
+        let floor = self.dungeon.floor_mut();
+        let index = floor
+            .monsters
+            .iter()
+            .position(|m| (m.col, m.row) == (col, row))
+            .expect("perform só ataca onde tem monstro");
+        let monster = &mut floor.monsters[index];
+        let attack = combat::resolve_attack(&self.player.effective_stats(), &monster.stats, &mut self.rng);
+        combat::apply_attack(&mut monster.stats, attack);
+
+        let name = monster.kind.name;
+        self.log.push(match attack {
+            Attack::Miss => format!("Você erra o {}.", name),
+            Attack::Hit { damage, killed: false } => format!("Você acerta o {} ({} de dano).", name, damage),
+            Attack::Hit { damage, killed: true } => format!("Você mata o {} ({} de dano)!", name, damage),
+        });
+
+        if monster.stats.is_dead() {
+            let monster = floor.monsters.remove(index);
+            let xp = bury(floor, monster, &mut self.rng);
+            self.award_xp(xp);
+        }
+    }
+
+    // --- Níveis ---
+    fn award_xp(&mut self, xp: u32) {
+        if self.player.stats.is_dead() || self.player.gain_xp(xp) == 0 {
+            return;
+        }
+        let experience = self.player.experience;
+        self.log.push(format!(
+            "Você sobe pro nível {}! Vida máxima {}. Vantagens pra escolher: {}.",
+            experience.level, self.player.stats.max_hp, experience.perk_points
+        ));
+    }
+
+    // Escolher vantagem não gasta turno. false se não tem ponto pra gastar.
+    pub fn choose_perk(&mut self, perk: Perk) -> bool {
+        if !self.player.choose_perk(perk) {
+            return false;
+        }
+        self.log.push(format!("Você escolhe {} ({}).", perk.label(), perk.describe()));
+        true
+    }
+
+    // --- Itens ---
+    // Pegar, usar, largar, equipar e tirar gastam um turno. Se não deu (nada no chão,
+    // inventário cheio, item que não se usa) só avisa e o turno não passa.
+    fn describe_items_here(&mut self) {
+        let (col, row) = self.player.tile();
+        let names: Vec<String> = self
+            .dungeon
+            .floor()
+            .items
+            .iter()
+            .rev()
+            .filter(|i| (i.col, i.row) == (col, row))
+            .map(|i| i.stack.label())
+            .collect();
+        if !names.is_empty() {
+            self.log.push(format!("Aqui tem: {}.", names.join(", ")));
+        }
+    }
+
+    fn pickup(&mut self) -> bool {
+        let (col, row) = self.player.tile();
+        let floor = self.dungeon.floor_mut();
+        let Some(index) = floor.item_at(col, row) else {
+            self.log.push("Não tem nada aqui pra pegar.".to_owned());
+            return false;
+        };
+        let stack = floor.items[index].stack;
+        match self.player.inventory.add(stack) {
+            Ok(()) => {
+                floor.items.remove(index);
+                self.log.push(format!("Você pega: {}.", stack.label()));
+                true
+            }
+            Err(e) => {
+                self.log.push(format!("{}: {}.", stack.label(), e));
+                false
+            }
+        }
+    }
+
+    fn use_item(&mut self, index: usize) -> bool {
+        let Some(stack) = self.player.inventory.stacks().get(index) else {
+            return false;
+        };
+        let Some(effect) = stack.kind.effect else {
+            self.log.push(format!("Não dá pra usar {}.", stack.kind.name));
+            return false;
+        };
+        let name = stack.kind.name;
+        self.player.inventory.take(index, 1);
+        match effect {
+            ItemEffect::Heal(dice) => {
+                let stats = &mut self.player.stats;
+                let healed = dice.roll(&mut self.rng).min(stats.max_hp - stats.hp).max(0);
+                stats.hp += healed;
+                self.log.push(format!("Você usa {} e recupera {} de vida.", name, healed));
+            }
+            ItemEffect::Status(status) => {
+                self.player.status.apply(status);
+                self.player.refresh_speed();
+                self.log.push(format!("Você usa {}: {}.", name, status.kind.label()));
+            }
+            ItemEffect::StatusInView(status) => {
+                let floor = self.dungeon.floor_mut();
+                let mut affected = 0;
+                for monster in floor.monsters.iter_mut().filter(|m| floor.visible.get(m.col, m.row)) {
+                    monster.status.apply(status);
+                    monster.refresh_speed();
+                    affected += 1;
+                }
+                self.log.push(match affected {
+                    0 => format!("Você lê {}, mas não tem ninguém por perto.", name),
+                    _ => format!("Você lê {}: {} em {} monstro(s).", name, status.kind.label(), affected),
+                });
+            }
+        }
+        true
+    }
+
+    fn drop_item(&mut self, index: usize) -> bool {
+        let Some(count) = self.player.inventory.stacks().get(index).map(|s| s.count) else {
+            return false;
+        };
+        let stack = self.player.inventory.take(index, count).expect("linha existe");
+        self.drop_stack(stack);
+        true
+    }
+
+    // Põe a pilha no tile do jogador (ver Floor::place_item)
+    fn drop_stack(&mut self, stack: ItemStack) {
+        let (col, row) = self.player.tile();
+        self.dungeon.floor_mut().place_item(col, row, stack);
+        self.log.push(format!("Você larga: {}.", stack.label()));
+    }
+
+    // O que estava no slot volta pro inventário. Se não couber, cai no chão.
+    fn equip_item(&mut self, index: usize) -> bool {
+        let Some(stack) = self.player.inventory.stacks().get(index) else {
+            return false;
+        };
+        if stack.kind.equip.is_none() {
+            self.log.push(format!("Não dá pra equipar {}.", stack.kind.name));
+            return false;
+        }
+        let stack = self.player.inventory.take(index, 1).expect("linha existe");
+        if let Some(old) = self.player.equipment.equip(stack) {
+            self.log.push(format!("Você tira {}.", old.kind.name));
+            if self.player.inventory.add(old).is_err() {
+                self.drop_stack(old);
+            }
+        }
+        self.log.push(format!("Você equipa {}.", stack.kind.name));
+        self.player.refresh_speed();
+        true
+    }
+
+    fn unequip_item(&mut self, slot: EquipSlot) -> bool {
+        let Some(&stack) = self.player.equipment.get(slot) else {
+            return false;
+        };
+        if let Err(e) = self.player.inventory.add(stack) {
+            self.log.push(format!("{}: {}.", stack.label(), e));
+            return false;
+        }
+        self.player.equipment.unequip(slot);
+        self.log.push(format!("Você tira {}.", stack.kind.name));
+        self.player.refresh_speed();
+        true
+    }
+
+    // --- Viagem ---
+    // Clique num tile conhecido: calcula o caminho e anda um passo por turno
+    // (follow_route), cada passo é um perform normal. Para se aparecer monstro
+    // hostil, se levar dano, se pisar num item ou se o caminho fechar.
+    // Auto-explorar é uma viagem sem destino fixo: a cada passo vai rumo ao
+    // tile inexplorado alcançável mais perto, até não sobrar nenhum.
+    pub fn travel_to(&mut self, target: (usize, usize), registry: &TileRegistry) {
+        self.cancel_route();
+        if target == self.player.tile() || self.too_confused() {
+            return;
+        }
+        if let Some(name) = self.hostile_in_view() {
+            self.log.push(format!("Não dá pra viajar com um {} por perto.", name));
+            return;
+        }
+        match find_route(self.dungeon.floor(), registry, self.player.tile(), target) {
+            Some(route) => self.route = route,
+            None => self.log.push("Não tem caminho conhecido até lá.".to_owned()),
+        }
+    }
+
+    pub fn explore(&mut self) {
+        self.cancel_route();
+        if self.too_confused() {
+            return;
+        }
+        if let Some(name) = self.hostile_in_view() {
+            self.log.push(format!("Não dá pra explorar com um {} por perto.", name));
+            return;
+        }
+        self.exploring = true;
+    }
+
+    pub fn is_traveling(&self) -> bool {
+        self.exploring || !self.route.is_empty()
+    }
+
+    pub fn cancel_route(&mut self) {
+        self.route.clear();
+        self.exploring = false;
+    }
+
+    // Dá o próximo passo da viagem. Retorna false se não andou (chegou ou parou).
+    pub fn follow_route(&mut self, registry: &TileRegistry) -> bool {
+        if self.exploring {
+            // Refeita a cada passo: o que apareceu no caminho muda o inexplorado mais perto
+            match explore_route(self.dungeon.floor(), registry, self.player.tile()) {
+                Some(route) if !route.is_empty() => self.route = route,
+                _ => {
+                    self.cancel_route();
+                    self.log.push("Nada mais pra explorar aqui.".to_owned());
+                    return false;
+                }
+            }
+        }
+        let Some(&(col, row)) = self.route.last() else {
+            return false;
+        };
+        if self.too_confused() {
+            self.cancel_route();
+            return false;
+        }
+        if let Some(name) = self.hostile_in_view() {
+            self.cancel_route();
+            self.log.push(format!("Você vê um {} e para.", name));
+            return false;
+        }
+        // Monstro parado no caminho: andar seria atacar, melhor parar
+        if self.dungeon.floor().monster_at(col, row).is_some() {
+            self.cancel_route();
+            return false;
+        }
+
+        let hp = self.player.stats.hp;
+        let dx = col as isize - self.player.col as isize;
+        let dy = row as isize - self.player.row as isize;
+        if !self.perform(Command::Move { dx, dy }, registry) {
+            self.cancel_route();
+            return false;
+        }
+        self.route.pop();
+        if self.player.stats.hp < hp {
+            self.cancel_route();
+            self.log.push("Você foi atacado e para.".to_owned());
+        } else if self.dungeon.floor().item_at(col, row).is_some() {
+            self.cancel_route(); // O "Aqui tem" já saiu no perform
+        }
+        true
+    }
+
+    // Confuso não dá pra viajar: cada passo poderia sair pra qualquer lado
+    fn too_confused(&mut self) -> bool {
+        let confused = self.player.status.has(StatusKind::Confusion);
+        if confused {
+            self.log.push("Você está confuso demais pra isso.".to_owned());
+        }
+        confused
+    }
+
+    // Jogador confuso: às vezes o passo sai numa direção qualquer, e se ela
+    // estiver fechada o turno vai embora do mesmo jeito (vira Wait).
+    // Chamado no movimento do InGame, antes do perform.
+    pub fn confuse(&mut self, command: Command, registry: &TileRegistry) -> Command {
+        let Command::Move { .. } = command else {
+            return command;
+        };
+        if !self.player.status.has(StatusKind::Confusion) || !self.rng.chance(CONFUSION_CHANCE) {
+            return command;
+        }
+        let (dx, dy) = NEIGHBORS[self.rng.range(0, NEIGHBORS.len())];
+        let from = (self.player.col as isize, self.player.row as isize);
+        if can_enter(&self.dungeon.floor().map, registry, from, (from.0 + dx, from.1 + dy)) {
+            self.log.push("Você cambaleia.".to_owned());
+            Command::Move { dx, dy }
+        } else {
+            self.log.push("Você cambaleia e tropeça.".to_owned());
+            Command::Wait
+        }
+    }
+
+    // Nome de algum monstro hostil que o jogador está vendo
+    fn hostile_in_view(&self) -> Option<&'static str> {
+        let floor = self.dungeon.floor();
+        floor
+            .monsters
+            .iter()
+            .find(|m| m.kind.faction == Faction::Hostile && floor.visible.get(m.col, m.row))
+            .map(|m| m.kind.name)
+    }
+
+    pub fn is_over(&self) -> bool {
+        self.player.stats.is_dead()
+    }
+
+    // Se o jogador está numa escada, pra qual profundidade ela leva.
+    pub fn stairs_destination(&self, registry: &TileRegistry) -> Option<u32> {
+        let map = &self.dungeon.floor().map;
+        let tile_char = map.tile(self.player.col as isize, self.player.row as isize);
+        let depth = self.dungeon.depth();
+
+        if registry.has_flag(tile_char, TileFlag::StairsDown) && depth < self.dungeon.max_depth() {
+            Some(depth + 1)
+        } else if registry.has_flag(tile_char, TileFlag::StairsUp) && depth > 1 {
+            Some(depth - 1)
+        } else {
+            None
+        }
+    }
+}
+
+// O jogador do ponto de vista do relógio: o que os monstros e os efeitos
+// mexem enquanto a energia dele está emprestada pro Scheduler
+struct Target<'a> {
+    tile: (usize, usize),
+    stats: &'a mut Stats,
+    gear: Bonus, // Equipamento do jogador, conta na defesa
+    status: &'a mut Statuses,
+}
+
+// Um turno de efeitos pra todo mundo no andar: vida por turno e o que acabou.
+// Monstro que morre de veneno vira cadáver como se tivesse apanhado (e dá XP igual).
+fn status_turn(floor: &mut Floor, target: &mut Target, rng: &mut Rng, log: &mut Vec<String>) -> u32 {
+    let tick = target.status.tick();
+    target.stats.hp = (target.stats.hp + tick.hp).min(target.stats.max_hp);
+    if tick.hp < 0 && target.stats.is_dead() {
+        log.push("O veneno mata você!".to_owned());
+    }
+    for kind in tick.expired {
+        log.push(format!("{} passou.", kind.label()));
+    }
+
+    for monster in &mut floor.monsters {
+        let tick = monster.status.tick();
+        monster.stats.hp = (monster.stats.hp + tick.hp).min(monster.stats.max_hp);
+        if !tick.expired.is_empty() {
+            monster.refresh_speed();
+        }
+        if monster.stats.is_dead() && floor.visible.get(monster.col, monster.row) {
+            log.push(format!("O {} morre envenenado.", monster.kind.name));
+        }
+    }
+    let (dead, alive): (Vec<Monster>, Vec<Monster>) =
+        std::mem::take(&mut floor.monsters).into_iter().partition(|m| m.stats.is_dead());
+    floor.monsters = alive;
+    dead.into_iter().map(|monster| bury(floor, monster, rng)).sum()
+}
+
+// Monstro morto: vira cadáver e larga no tile o que sair da tabela de drops dele.
+// Devolve o XP que ele vale.
+fn bury(floor: &mut Floor, monster: Monster, rng: &mut Rng) -> u32 {
+    if let Some(drops) = &monster.kind.drops {
+        for kind in drops.roll(rng) {
+            let stack = ItemStack::new(kind, item::roll_amount(kind, floor.depth, rng));
+            floor.place_item(monster.col, monster.row, stack);
+        }
+    }
+    floor.corpses.push(Corpse { col: monster.col, row: monster.row, kind: monster.kind });
+    monster.kind.xp
+}
+
+// Um tick pros monstros do andar: todos ganham energia e quem puder age.
+fn monsters_tick(floor: &mut Floor, target: &mut Target, registry: &TileRegistry, rng: &mut Rng, log: &mut Vec<String>) {
+    // Onde cada um está, pra ninguém entrar no tile de outro
+    let mut positions: Vec<(usize, usize)> = floor.monsters.iter().map(|m| (m.col, m.row)).collect();
+
+    for (i, monster) in floor.monsters.iter_mut().enumerate() {
+        monster.energy.gain();
+        while monster.energy.is_ready() && !target.stats.is_dead() {
+            let occupied = |col, row| positions.iter().enumerate().any(|(j, &tile)| j != i && tile == (col, row));
+            match monster.choose_action(&floor.map, registry, &floor.visible, target.tile, occupied, rng) {
+                Some(Action::Move { col, row }) => {
+                    monster.step_to(col, row);
+                    positions[i] = (col, row);
+                }
+                Some(Action::Attack) => {
+                    let attack = combat::resolve_attack(&monster.stats, &target.gear.apply(target.stats), rng);
+                    combat::apply_attack(target.stats, attack);
+                    let name = monster.kind.name;
+                    log.push(match attack {
+                        Attack::Miss => format!("O {} erra você.", name),
+                        Attack::Hit { damage, killed: false } => format!("O {} acerta você ({} de dano).", name, damage),
+                        Attack::Hit { damage, killed: true } => format!("O {} mata você ({} de dano)!", name, damage),
+                    });
+                    // Ataque que acerta pode deixar um efeito (veneno do goblin...)
+                    if let Attack::Hit { killed: false, .. } = attack
+                        && let Some(effect) = monster.kind.on_hit
+                    {
+                        target.status.apply(effect);
+                        log.push(format!("{}!", effect.kind.label()));
+                    }
+                }
+                None => {}
+            }
+            monster.energy.spend(ACTION_COST);
+        }
+    }
+}
+
+// Custo de um passo da viagem: só por tiles já explorados. Escada só entra se
+// for o goal, senão a viagem trocaria de andar no meio. No find_route o goal é
+// o destino; no explore_route é o tile do jogador, que pode estar numa escada
+// (chegando num andar ele sempre está).
+fn known_step<'a>(
+    floor: &'a Floor,
+    registry: &'a TileRegistry,
+    goal: Option<(usize, usize)>,
+) -> impl Fn((usize, usize), (usize, usize)) -> Option<u32> + 'a {
+    move |(fc, fr), (tc, tr)| {
+        let glyph = floor.map.tile(tc as isize, tr as isize);
+        let stairs = registry.has_flag(glyph, TileFlag::StairsDown) || registry.has_flag(glyph, TileFlag::StairsUp);
+        let allowed = floor.explored.get(tc, tr)
+            && (!stairs || Some((tc, tr)) == goal)
+            && can_enter(&floor.map, registry, (fc as isize, fr as isize), (tc as isize, tr as isize));
+        allowed.then_some(1)
+    }
+}
+
+// Caminho mais curto (A*, 8 direções) de from até to por tiles conhecidos.
+// Retorna os passos ao contrário (o próximo no fim), sem o tile de partida.
+fn find_route(floor: &Floor, registry: &TileRegistry, from: (usize, usize), to: (usize, usize)) -> Option<Vec<(usize, usize)>> {
+    let map = &floor.map;
+    let mut route = path::astar(map.width, map.height, from, to, Connectivity::Eight, known_step(floor, registry, Some(to)))?;
+    route.reverse();
+    Some(route)
+}
+
+// Caminho até a beira do explorado mais perto: tile conhecido e andável com
+// algum vizinho (dentro do mapa) ainda não explorado. Chegando lá o campo de
+// visão revela o vizinho. Mesmo formato do find_route.
+fn explore_route(floor: &Floor, registry: &TileRegistry, from: (usize, usize)) -> Option<Vec<(usize, usize)>> {
+    let map = &floor.map;
+    let unexplored = |col: usize, row: usize, (dx, dy): (isize, isize)| {
+        match (col.checked_add_signed(dx), row.checked_add_signed(dy)) {
+            (Some(c), Some(r)) => c < map.width && r < map.height && !floor.explored.get(c, r),
+            _ => false,
+        }
+    };
+    let frontier: Vec<(usize, usize)> = map
+        .tiles()
+        .filter(|&(col, row, glyph)| {
+            floor.explored.get(col, row)
+                && registry.is_walkable(glyph)
+                && !registry.has_flag(glyph, TileFlag::StairsDown)
+                && !registry.has_flag(glyph, TileFlag::StairsUp)
+                && NEIGHBORS.iter().any(|&offset| unexplored(col, row, offset))
+        })
+        .map(|(col, row, _)| (col, row))
+        .collect();
+
+    let field = DijkstraMap::new(map.width, map.height, frontier, Connectivity::Eight, known_step(floor, registry, Some(from)));
+    let mut route = field.path_from(from)?;
+    route.reverse();
+    Some(route)
+}
+
+// Os 8 vizinhos de um tile
+pub const NEIGHBORS: [(isize, isize); 8] = [(1, 0), (-1, 0), (0, 1), (0, -1), (1, 1), (1, -1), (-1, 1), (-1, -1)];
+
+// Checagem de colisão: dá pra ir de from pro tile vizinho to?
+// A tabela de tiles diz o que é andável. Fora do mapa (' ') nunca é.
+// Na diagonal, os dois tiles retos do lado (as "quinas") contam conforme a
+// corner_rule do tiles.cfg.
+pub fn can_enter(map: &Map, registry: &TileRegistry, from: (isize, isize), to: (isize, isize)) -> bool {
+    let (col, row) = to;
+    if !registry.is_walkable(map.tile(col, row)) {
+        return false;
+    }
+    if col == from.0 || row == from.1 {
+        return true;
+    }
+    let corners = [(col, from.1), (from.0, row)];
+    let blocked = corners.iter().filter(|&&(c, r)| !registry.is_walkable(map.tile(c, r))).count();
+    match registry.corner_rule() {
+        CornerRule::Free => true,
+        CornerRule::NoSqueeze => blocked < 2,
+        CornerRule::NoCut => blocked == 0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::content;
+    use crate::fov::TileMask;
+    use crate::item;
+
+    fn registry() -> TileRegistry {
+        content::tests::load_shipped();
+        TileRegistry::parse(&std::fs::read_to_string("assets/tiles.cfg").unwrap()).unwrap()
+    }
+
+    // Corredor de uma linha entre paredes, com as primeiras colunas exploradas
+    fn corridor(middle: &str, explored_cols: usize) -> Floor {
+        let width = middle.len();
+        let wall = "x".repeat(width);
+        let tiles: Vec<char> = [wall.as_str(), middle, wall.as_str()].iter().flat_map(|row| row.chars()).collect();
+        let map = Map::from_grid("teste".into(), "teste".into(), width, 3, tiles);
+        let mut explored = TileMask::new(width, 3);
+        for row in 0..3 {
+            for col in 0..explored_cols {
+                explored.set(col, row);
+            }
+        }
+        Floor { depth: 2, map, visible: TileMask::new(width, 3), explored, monsters: vec![], corpses: vec![], items: vec![] }
+    }
+
+    #[test]
+    fn explore_starting_on_stairs() {
+        let registry = registry();
+        for middle in ["x<oooox", "x>oooox"] {
+            let floor = corridor(middle, 3);
+            assert_eq!(explore_route(&floor, &registry, (1, 1)), Some(vec![(2, 1)]));
+
+            let mut run = Run::new(Dungeon::restore(1, vec![floor], 1, 3), Player::new(1, 1), 0);
+            run.explore();
+            assert!(run.follow_route(&registry));
+            assert_eq!(run.player.tile(), (2, 1));
+        }
+    }
+
+    #[test]
+    fn explore_does_not_cross_stairs() {
+        // A beira do explorado é a coluna 3, mas o caminho até ela passa pela escada
+        let floor = corridor("xo>oooox", 4);
+        assert_eq!(explore_route(&floor, &registry(), (1, 1)), None);
+    }
+
+    #[test]
+    fn equip_swap_goes_through_the_inventory() {
+        let registry = registry();
+        let mut run = Run::new(Dungeon::restore(1, vec![corridor("xooooox", 7)], 1, 3), Player::new(1, 1), 0);
+        let dagger = ItemStack::new(item::kind_for_glyph(')').unwrap(), 1);
+        run.player.inventory.add(dagger).unwrap();
+        run.player.inventory.add(dagger).unwrap();
+
+        assert!(run.perform(Command::Equip { index: 0 }, &registry));
+        assert_eq!(run.player.inventory.stacks().len(), 1);
+        // A adaga equipada volta pro inventário no lugar da nova
+        assert!(run.perform(Command::Equip { index: 0 }, &registry));
+        assert_eq!(run.player.inventory.stacks().len(), 1);
+        assert!(run.player.equipment.get(EquipSlot::Weapon).is_some());
+
+        assert!(run.perform(Command::Unequip { slot: EquipSlot::Weapon }, &registry));
+        assert_eq!(run.player.inventory.stacks().len(), 2);
+        assert!(!run.perform(Command::Unequip { slot: EquipSlot::Weapon }, &registry));
+    }
+
+    #[test]
+    fn unequip_refused_when_it_does_not_fit() {
+        let registry = registry();
+        let mut run = Run::new(Dungeon::restore(1, vec![corridor("xooooox", 7)], 1, 3), Player::new(1, 1), 0);
+        run.player.equipment.equip(ItemStack::new(item::kind_for_glyph('[').unwrap(), 1));
+        run.player.inventory.capacity = 10;
+        assert!(!run.perform(Command::Unequip { slot: EquipSlot::Armor }, &registry));
+        assert!(run.player.equipment.get(EquipSlot::Armor).is_some());
+    }
+}
@@ -0,0 +1,200 @@
+use std::collections::HashSet;
+
+use crate::input::Action;
+
+// --- GAMEPAD ---
+// O controle vira as mesmas ações do teclado (ver input.rs), então menus e jogo
+// não sabem de onde veio o aperto. Mapeamento fixo, estilo Xbox:
+//
+//   D-pad / analógico esquerdo  andar (8 direções) e navegar nos menus
+//   A (baixo)                   confirm, skip
+//   B (direita)                 cancel
+//   X (esquerda)                wait
+//   Y (cima)                    save
+//   RB                          explore
+//   LB                          pickup
+//   LT                          inventory
+//   RT                          drop
+//   RS (apertar o analógico)    character
+//   Start                       quit_to_menu
+//   Select / Back               controls
+//
+// Só lê o controle de verdade com a feature "gamepad" (usa gilrs, que no Linux
+// precisa da libudev). Sem ela Gamepads::poll nunca aperta nada.
+#[cfg(feature = "gamepad")]
+use gilrs::{Axis, Button, EventType, Gilrs};
+
+// Botão -> ação. Direções ficam de fora, saem do D-pad/analógico.
+#[cfg(feature = "gamepad")]
+const BUTTONS: [(Button, &[Action]); 11] = [
+    (Button::South, &[Action::Confirm, Action::Skip]),
+    (Button::East, &[Action::Cancel]),
+    (Button::West, &[Action::Wait]),
+    (Button::North, &[Action::Save]),
+    (Button::RightTrigger, &[Action::Explore]),
+    (Button::LeftTrigger, &[Action::Pickup]),
+    (Button::LeftTrigger2, &[Action::Inventory]),
+    (Button::RightTrigger2, &[Action::Drop]),
+    (Button::RightThumb, &[Action::Character]),
+    (Button::Start, &[Action::QuitToMenu]),
+    (Button::Select, &[Action::Controls]),
+];
+
+// Setor do analógico (0 = direita, sentido horário com y pra baixo) -> ação
+#[cfg_attr(not(feature = "gamepad"), allow(dead_code))]
+const DIRECTIONS: [Action; 8] = [
+    Action::MoveRight,
+    Action::MoveDownRight,
+    Action::MoveDown,
+    Action::MoveDownLeft,
+    Action::MoveLeft,
+    Action::MoveUpLeft,
+    Action::MoveUp,
+    Action::MoveUpRight,
+];
+
+// Ações seguradas nesse frame e as que começaram nesse frame
+#[derive(Debug, Default, Clone)]
+pub struct PadState {
+    down: HashSet<Action>,
+    pressed: HashSet<Action>,
+}
+
+impl PadState {
+    pub fn down(&self, action: Action) -> bool {
+        self.down.contains(&action)
+    }
+
+    pub fn pressed(&self, action: Action) -> bool {
+        self.pressed.contains(&action)
+    }
+}
+
+pub struct Gamepads {
+    #[cfg(feature = "gamepad")]
+    gilrs: Option<Gilrs>, // None se o sistema de controles não iniciou
+    state: PadState,
+}
+
+impl Gamepads {
+    // Nunca falha: sem suporte a controle o jogo segue só no teclado
+    pub fn new() -> Self {
+        #[cfg(feature = "gamepad")]
+        let gilrs = match Gilrs::new() {
+            Ok(gilrs) => {
+                for (_, gamepad) in gilrs.gamepads() {
+                    println!("Controle encontrado: {}", gamepad.name());
+                }
+                Some(gilrs)
+            }
+            Err(e) => {
+                println!("Controles indisponíveis: {}", e);
+                None
+            }
+        };
+        Gamepads {
+            #[cfg(feature = "gamepad")]
+            gilrs,
+            state: PadState::default(),
+        }
+    }
+
+    // Chamar uma vez por frame. Junta todos os controles conectados.
+    // deadzone: o analógico só conta a partir desse tanto (0 a 1).
+    pub fn poll(&mut self, deadzone: f32) -> PadState {
+        let down = self.read(deadzone);
+        let pressed = down.difference(&self.state.down).copied().collect();
+        self.state = PadState { down, pressed };
+        self.state.clone()
+    }
+
+    #[cfg(feature = "gamepad")]
+    fn read(&mut self, deadzone: f32) -> HashSet<Action> {
+        let mut down = HashSet::new();
+        let Some(gilrs) = self.gilrs.as_mut() else {
+            return down;
+        };
+
+        // Os eventos precisam ser consumidos pro estado dos controles atualizar.
+        // Conectar/desconectar no meio do jogo só avisa: quem estiver ligado conta.
+        while let Some(event) = gilrs.next_event() {
+            match event.event {
+                EventType::Connected => println!("Controle conectado: {}", gilrs.gamepad(event.id).name()),
+                EventType::Disconnected => println!("Controle desconectado: {}", gilrs.gamepad(event.id).name()),
+                _ => {}
+            }
+        }
+
+        for (_, gamepad) in gilrs.gamepads() {
+            for (button, actions) in BUTTONS {
+                if gamepad.is_pressed(button) {
+                    down.extend(actions.iter().copied());
+                }
+            }
+
+            // D-pad tem prioridade; solto, vale o analógico
+            let axis = |negative, positive| (gamepad.is_pressed(positive) as i32 - gamepad.is_pressed(negative) as i32) as f32;
+            let mut x = axis(Button::DPadLeft, Button::DPadRight);
+            let mut y = axis(Button::DPadUp, Button::DPadDown);
+            if x == 0.0 && y == 0.0 {
+                x = gamepad.value(Axis::LeftStickX);
+                y = -gamepad.value(Axis::LeftStickY); // No gilrs cima é positivo
+            }
+            if let Some(action) = direction(x, y, deadzone) {
+                down.insert(action);
+            }
+        }
+        down
+    }
+
+    #[cfg(not(feature = "gamepad"))]
+    fn read(&mut self, _deadzone: f32) -> HashSet<Action> {
+        HashSet::new()
+    }
+}
+
+// Direção do analógico em um dos 8 setores de 45 graus. Dentro da deadzone, nada.
+#[cfg_attr(not(feature = "gamepad"), allow(dead_code))]
+fn direction(x: f32, y: f32, deadzone: f32) -> Option<Action> {
+    if x.hypot(y) < deadzone.max(f32::EPSILON) {
+        return None;
+    }
+    let sector = (y.atan2(x) / std::f32::consts::FRAC_PI_4).round() as i32;
+    Some(DIRECTIONS[sector.rem_euclid(8) as usize])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn deadzone_edge_counts_as_pushed() {
+        assert_eq!(direction(0.5, 0.0, 0.5), Some(Action::MoveRight));
+        assert_eq!(direction(0.49, 0.0, 0.5), None);
+        assert_eq!(direction(0.3, -0.3, 0.5), None); // Diagonal: vale o comprimento, não cada eixo
+        // Deadzone 0 não faz o analógico parado virar direção
+        assert_eq!(direction(0.0, 0.0, 0.0), None);
+    }
+
+    #[test]
+    fn each_sector_is_45_degrees() {
+        // y pra baixo: 90 graus é MoveDown
+        let expected = [
+            Action::MoveRight,
+            Action::MoveDownRight,
+            Action::MoveDown,
+            Action::MoveDownLeft,
+            Action::MoveLeft,
+            Action::MoveUpLeft,
+            Action::MoveUp,
+            Action::MoveUpRight,
+        ];
+        for (sector, &action) in expected.iter().enumerate() {
+            // No centro do setor e perto das duas bordas (±22.5 graus)
+            for offset in [0.0f32, -20.0, 20.0] {
+                let angle = (sector as f32 * 45.0 + offset).to_radians();
+                assert_eq!(direction(angle.cos(), angle.sin(), 0.5), Some(action), "setor {} {:+}", sector, offset);
+            }
+        }
+    }
+}
@@ -0,0 +1,570 @@
+use macroquad::prelude::*;
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+
+use crate::gamepad::PadState;
+
+// --- INPUT ---
+// Os estados do jogo perguntam por ações (Confirm, MoveUp...), nunca por teclas.
+// Quais teclas disparam cada ação vem de um arquivo do usuário
+// (ex: ~/.config/aalg/input.cfg no Linux), uma linha por ação:
+//
+//   # acao      teclas (separadas por vírgula)
+//   confirm     Enter,Z
+//   move_up     Up
+//
+// Ação que não aparece no arquivo fica com as teclas padrão. Sem arquivo,
+// tudo padrão. Os nomes das teclas são os do KeyCode do macroquad.
+// Linhas especiais ajustam a repetição de tecla segurada (em segundos) e a
+// deadzone do analógico do controle (0 a 1):
+//
+//   repeat_delay     0.25
+//   repeat_interval  0.1
+//   stick_deadzone   0.5
+const APP_DIR: &str = "aalg";
+const CONFIG_FILE: &str = "input.cfg";
+
+const DEFAULT_REPEAT_DELAY: f32 = 0.25; // Segurando a tecla, quanto espera até começar a repetir
+const DEFAULT_REPEAT_INTERVAL: f32 = 0.1; // Depois disso, intervalo mínimo entre repetições
+const DEFAULT_STICK_DEADZONE: f32 = 0.5; // Analógico mais perto do centro que isso conta como solto
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Action {
+    Confirm,    // Menus: escolhe a opção
+    Cancel,     // Menus: volta
+    Skip,       // Pula o splash
+    MoveUp,     // No jogo anda, nos menus sobe a seleção
+    MoveDown,   // No jogo anda, nos menus desce a seleção
+    MoveLeft,
+    MoveRight,
+    MoveUpLeft, // Diagonais, só no jogo
+    MoveUpRight,
+    MoveDownLeft,
+    MoveDownRight,
+    Wait,       // Passa o turno
+    Explore,    // Anda sozinho até o inexplorado mais perto
+    Pickup,     // Pega o item do chão
+    Inventory,  // Abre/fecha o inventário
+    Drop,       // No inventário: larga o item selecionado
+    Character,  // Abre/fecha a tela de personagem (nível e vantagens)
+    Save,       // Salva no slot do jogo
+    Controls,   // Abre a tela de controles
+    QuitToMenu, // Sai do jogo pro menu principal
+}
+
+impl Action {
+    // Ordem da tela de controles
+    pub const ALL: [Action; 20] = [
+        Action::MoveUp,
+        Action::MoveDown,
+        Action::MoveLeft,
+        Action::MoveRight,
+        Action::MoveUpLeft,
+        Action::MoveUpRight,
+        Action::MoveDownLeft,
+        Action::MoveDownRight,
+        Action::Wait,
+        Action::Explore,
+        Action::Pickup,
+        Action::Inventory,
+        Action::Drop,
+        Action::Character,
+        Action::Confirm,
+        Action::Cancel,
+        Action::Skip,
+        Action::Save,
+        Action::Controls,
+        Action::QuitToMenu,
+    ];
+
+    // Nome no input.cfg
+    pub fn name(self) -> &'static str {
+        match self {
+            Action::Confirm => "confirm",
+            Action::Cancel => "cancel",
+            Action::Skip => "skip",
+            Action::MoveUp => "move_up",
+            Action::MoveDown => "move_down",
+            Action::MoveLeft => "move_left",
+            Action::MoveRight => "move_right",
+            Action::MoveUpLeft => "move_up_left",
+            Action::MoveUpRight => "move_up_right",
+            Action::MoveDownLeft => "move_down_left",
+            Action::MoveDownRight => "move_down_right",
+            Action::Wait => "wait",
+            Action::Explore => "explore",
+            Action::Pickup => "pickup",
+            Action::Inventory => "inventory",
+            Action::Drop => "drop",
+            Action::Character => "character",
+            Action::Save => "save",
+            Action::Controls => "controls",
+            Action::QuitToMenu => "quit_to_menu",
+        }
+    }
+
+    // Nome na tela de controles
+    pub fn label(self) -> &'static str {
+        match self {
+            Action::Confirm => "Confirmar",
+            Action::Cancel => "Cancelar / voltar",
+            Action::Skip => "Pular abertura",
+            Action::MoveUp => "Andar pra cima",
+            Action::MoveDown => "Andar pra baixo",
+            Action::MoveLeft => "Andar pra esquerda",
+            Action::MoveRight => "Andar pra direita",
+            Action::MoveUpLeft => "Andar pra cima e esquerda",
+            Action::MoveUpRight => "Andar pra cima e direita",
+            Action::MoveDownLeft => "Andar pra baixo e esquerda",
+            Action::MoveDownRight => "Andar pra baixo e direita",
+            Action::Wait => "Esperar um turno",
+            Action::Explore => "Auto-explorar",
+            Action::Pickup => "Pegar item",
+            Action::Inventory => "Inventário",
+            Action::Drop => "Largar item (no inventário)",
+            Action::Character => "Personagem",
+            Action::Save => "Salvar",
+            Action::Controls => "Controles",
+            Action::QuitToMenu => "Voltar ao menu",
+        }
+    }
+
+    fn parse(s: &str) -> Option<Self> {
+        Action::ALL.into_iter().find(|action| action.name() == s)
+    }
+
+    // Onde a ação é lida. Duas ações do mesmo contexto não podem dividir tecla
+    fn contexts(self) -> &'static [Context] {
+        match self {
+            Action::Skip => &[Context::Splash],
+            Action::Confirm | Action::Cancel => &[Context::Menu],
+            Action::MoveUp | Action::MoveDown | Action::Inventory | Action::Character => &[Context::Menu, Context::Game],
+            Action::Drop => &[Context::Menu],
+            Action::MoveLeft
+            | Action::MoveRight
+            | Action::MoveUpLeft
+            | Action::MoveUpRight
+            | Action::MoveDownLeft
+            | Action::MoveDownRight
+            | Action::Wait
+            | Action::Explore
+            | Action::Pickup
+            | Action::Save
+            | Action::Controls
+            | Action::QuitToMenu => &[Context::Game],
+        }
+    }
+
+    fn default_keys(self) -> &'static [KeyCode] {
+        match self {
+            Action::Confirm => &[KeyCode::Enter, KeyCode::Z],
+            Action::Cancel => &[KeyCode::X, KeyCode::Escape],
+            Action::Skip => &[KeyCode::Enter, KeyCode::Z, KeyCode::X, KeyCode::C, KeyCode::D],
+            // Setas, teclado numérico e as teclas do vi (hjkl + yubn nas diagonais)
+            Action::MoveUp => &[KeyCode::Up, KeyCode::Kp8, KeyCode::K],
+            Action::MoveDown => &[KeyCode::Down, KeyCode::Kp2, KeyCode::J],
+            Action::MoveLeft => &[KeyCode::Left, KeyCode::Kp4, KeyCode::H],
+            Action::MoveRight => &[KeyCode::Right, KeyCode::Kp6, KeyCode::L],
+            Action::MoveUpLeft => &[KeyCode::Kp7, KeyCode::Y],
+            Action::MoveUpRight => &[KeyCode::Kp9, KeyCode::U],
+            Action::MoveDownLeft => &[KeyCode::Kp1, KeyCode::B],
+            Action::MoveDownRight => &[KeyCode::Kp3, KeyCode::N],
+            Action::Wait => &[KeyCode::Space, KeyCode::Kp5, KeyCode::Period],
+            Action::Explore => &[KeyCode::O],
+            Action::Pickup => &[KeyCode::G, KeyCode::Comma],
+            Action::Inventory => &[KeyCode::I],
+            Action::Drop => &[KeyCode::D],
+            Action::Character => &[KeyCode::C],
+            Action::Save => &[KeyCode::F5],
+            Action::Controls => &[KeyCode::F1],
+            Action::QuitToMenu => &[KeyCode::Escape],
+        }
+    }
+}
+
+// Enter no splash e nos menus pode (nunca são lidos juntos), F5 pra salvar e
+// pra andar não.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Context {
+    Splash,
+    Menu, // Menus e telas por cima do jogo (controles, inventário, personagem)
+    Game,
+}
+
+// Teclas que dá pra usar nos controles (e escrever no input.cfg)
+const KEYS: [KeyCode; 97] = [
+    KeyCode::Space, KeyCode::Apostrophe, KeyCode::Comma, KeyCode::Minus, KeyCode::Period, KeyCode::Slash,
+    KeyCode::Key0, KeyCode::Key1, KeyCode::Key2, KeyCode::Key3, KeyCode::Key4,
+    KeyCode::Key5, KeyCode::Key6, KeyCode::Key7, KeyCode::Key8, KeyCode::Key9,
+    KeyCode::Semicolon, KeyCode::Equal,
+    KeyCode::A, KeyCode::B, KeyCode::C, KeyCode::D, KeyCode::E, KeyCode::F, KeyCode::G,
+    KeyCode::H, KeyCode::I, KeyCode::J, KeyCode::K, KeyCode::L, KeyCode::M, KeyCode::N,
+    KeyCode::O, KeyCode::P, KeyCode::Q, KeyCode::R, KeyCode::S, KeyCode::T, KeyCode::U,
+    KeyCode::V, KeyCode::W, KeyCode::X, KeyCode::Y, KeyCode::Z,
+    KeyCode::LeftBracket, KeyCode::Backslash, KeyCode::RightBracket, KeyCode::GraveAccent,
+    KeyCode::Escape, KeyCode::Enter, KeyCode::Tab, KeyCode::Backspace, KeyCode::Insert, KeyCode::Delete,
+    KeyCode::Right, KeyCode::Left, KeyCode::Down, KeyCode::Up,
+    KeyCode::PageUp, KeyCode::PageDown, KeyCode::Home, KeyCode::End,
+    KeyCode::F1, KeyCode::F2, KeyCode::F3, KeyCode::F4, KeyCode::F5, KeyCode::F6,
+    KeyCode::F7, KeyCode::F8, KeyCode::F9, KeyCode::F10, KeyCode::F11, KeyCode::F12,
+    KeyCode::Kp0, KeyCode::Kp1, KeyCode::Kp2, KeyCode::Kp3, KeyCode::Kp4,
+    KeyCode::Kp5, KeyCode::Kp6, KeyCode::Kp7, KeyCode::Kp8, KeyCode::Kp9,
+    KeyCode::KpDecimal, KeyCode::KpDivide, KeyCode::KpMultiply, KeyCode::KpSubtract, KeyCode::KpAdd,
+    KeyCode::KpEnter, KeyCode::KpEqual,
+    KeyCode::LeftShift, KeyCode::LeftControl, KeyCode::LeftAlt,
+    KeyCode::RightShift, KeyCode::RightControl, KeyCode::RightAlt,
+];
+
+// Nome da tecla = nome da variante no KeyCode ("Enter", "Kp8", "F5"...)
+pub fn key_name(key: KeyCode) -> String {
+    format!("{:?}", key)
+}
+
+fn parse_key(s: &str) -> Option<KeyCode> {
+    KEYS.into_iter().find(|&key| key_name(key) == s)
+}
+
+// Dá pra usar essa tecla nos controles?
+pub fn is_bindable(key: KeyCode) -> bool {
+    KEYS.contains(&key)
+}
+
+// --- Erros ---
+#[derive(Debug)]
+pub enum InputError {
+    NoConfigDir, // Sistema sem pasta de configuração do usuário
+    Io { path: String, source: io::Error },
+    Syntax { line: usize, message: String },
+}
+
+impl fmt::Display for InputError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InputError::NoConfigDir => write!(f, "pasta de configuração do usuário não encontrada"),
+            InputError::Io { path, source } => write!(f, "não foi possível acessar '{}': {}", path, source),
+            InputError::Syntax { line, message } => write!(f, "linha {}: {}", line, message),
+        }
+    }
+}
+
+impl std::error::Error for InputError {}
+
+// --- Bindings ---
+pub struct Bindings {
+    keys: HashMap<Action, Vec<KeyCode>>,
+    pub repeat_delay: f32,
+    pub repeat_interval: f32,
+    pub stick_deadzone: f32,
+    pad: PadState, // Controle nesse frame, ver gamepad.rs
+}
+
+impl Default for Bindings {
+    fn default() -> Self {
+        Bindings {
+            keys: Action::ALL.into_iter().map(|action| (action, action.default_keys().to_vec())).collect(),
+            repeat_delay: DEFAULT_REPEAT_DELAY,
+            repeat_interval: DEFAULT_REPEAT_INTERVAL,
+            stick_deadzone: DEFAULT_STICK_DEADZONE,
+            pad: PadState::default(),
+        }
+    }
+}
+
+impl Bindings {
+    pub fn config_path() -> Result<PathBuf, InputError> {
+        dirs::config_dir()
+            .map(|dir| dir.join(APP_DIR).join(CONFIG_FILE))
+            .ok_or(InputError::NoConfigDir)
+    }
+
+    // Lê o input.cfg do usuário. Arquivo que não existe = tudo padrão.
+    pub fn load() -> Result<Self, InputError> {
+        let path = Self::config_path()?;
+        match fs::read_to_string(&path) {
+            Ok(text) => Self::parse(&text),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(source) => Err(InputError::Io { path: path.display().to_string(), source }),
+        }
+    }
+
+    pub fn parse(text: &str) -> Result<Self, InputError> {
+        let mut bindings = Self::default();
+
+        for (idx, line) in text.lines().enumerate() {
+            let line_no = idx + 1;
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let parts: Vec<&str> = line.split_whitespace().collect();
+            let [name, key_list] = parts[..] else {
+                return Err(syntax(line_no, "esperava: acao teclas"));
+            };
+
+            if name == "repeat_delay" || name == "repeat_interval" {
+                let seconds: f32 = match key_list.parse() {
+                    Ok(s) if s >= 0.0 => s,
+                    _ => return Err(syntax(line_no, &format!("{} deve ser um número de segundos: '{}'", name, key_list))),
+                };
+                if name == "repeat_delay" {
+                    bindings.repeat_delay = seconds;
+                } else {
+                    bindings.repeat_interval = seconds;
+                }
+                continue;
+            }
+            if name == "stick_deadzone" {
+                bindings.stick_deadzone = match key_list.parse() {
+                    Ok(d) if (0.0..1.0).contains(&d) => d,
+                    _ => return Err(syntax(line_no, &format!("stick_deadzone deve ser um número entre 0 e 1: '{}'", key_list))),
+                };
+                continue;
+            }
+
+            let action = Action::parse(name).ok_or_else(|| syntax(line_no, &format!("ação desconhecida: '{}'", name)))?;
+
+            let mut keys = Vec::new();
+            for key in key_list.split(',') {
+                keys.push(parse_key(key).ok_or_else(|| syntax(line_no, &format!("tecla desconhecida: '{}'", key)))?);
+            }
+            bindings.keys.insert(action, keys);
+        }
+
+        Ok(bindings)
+    }
+
+    pub fn save(&self) -> Result<(), InputError> {
+        let path = Self::config_path()?;
+        let io_error = |source| InputError::Io { path: path.display().to_string(), source };
+
+        fs::create_dir_all(path.parent().expect("config_path sempre tem pasta")).map_err(io_error)?;
+        fs::write(&path, self.to_text()).map_err(io_error)
+    }
+
+    // Todas as ações, pra o arquivo servir de lista do que dá pra mudar.
+    fn to_text(&self) -> String {
+        let mut lines = vec![
+            "# Controles. Uma linha por ação: acao  teclas (separadas por vírgula)".to_owned(),
+            "# Nomes das teclas: os do KeyCode do macroquad (Enter, Escape, A, Key1, Kp8, F5, Up...)".to_owned(),
+        ];
+        for action in Action::ALL {
+            let keys: Vec<String> = self.keys(action).iter().map(|&key| key_name(key)).collect();
+            lines.push(format!("{:<16}{}", action.name(), keys.join(",")));
+        }
+        lines.push("# Tecla segurada: espera repeat_delay e repete a cada repeat_interval (segundos)".to_owned());
+        lines.push(format!("{:<16}{}", "repeat_delay", self.repeat_delay));
+        lines.push(format!("{:<16}{}", "repeat_interval", self.repeat_interval));
+        lines.push("# Controle: o analógico só conta a partir desse tanto (0 a 1)".to_owned());
+        lines.push(format!("{:<16}{}", "stick_deadzone", self.stick_deadzone));
+        lines.push(String::new());
+        lines.join("\n")
+    }
+
+    pub fn keys(&self, action: Action) -> &[KeyCode] {
+        self.keys.get(&action).map_or(&[], Vec::as_slice)
+    }
+
+    // Alguma tecla (ou botão do controle) da ação foi apertada nesse frame?
+    pub fn pressed(&self, action: Action) -> bool {
+        self.pad.pressed(action) || self.keys(action).iter().any(|&key| is_key_pressed(key))
+    }
+
+    // Alguma tecla (ou botão do controle) da ação está segurada?
+    pub fn down(&self, action: Action) -> bool {
+        self.pad.down(action) || self.keys(action).iter().any(|&key| is_key_down(key))
+    }
+
+    // Estado do controle desse frame (Gamepads::poll)
+    pub fn set_pad(&mut self, pad: PadState) {
+        self.pad = pad;
+    }
+
+    // Troca todas as teclas da ação por uma só. Se a tecla já dispara outra ação
+    // no mesmo contexto, não troca e devolve essa outra ação.
+    pub fn rebind(&mut self, action: Action, key: KeyCode) -> Result<(), Action> {
+        if let Some(other) = self.conflict(action, key) {
+            return Err(other);
+        }
+        self.keys.insert(action, vec![key]);
+        Ok(())
+    }
+
+    fn conflict(&self, action: Action, key: KeyCode) -> Option<Action> {
+        Action::ALL.into_iter().find(|&other| {
+            other != action
+                && other.contexts().iter().any(|context| action.contexts().contains(context))
+                && self.keys(other).contains(&key)
+        })
+    }
+
+    // Volta as teclas pro padrão, mantendo os tempos de repetição
+    pub fn reset_keys(&mut self) {
+        self.keys = Bindings::default().keys;
+    }
+
+    // "Enter / Z", pra mostrar na tela
+    pub fn describe(&self, action: Action) -> String {
+        let keys: Vec<String> = self.keys(action).iter().map(|&key| key_name(key)).collect();
+        if keys.is_empty() { "-".to_owned() } else { keys.join(" / ") }
+    }
+}
+
+// --- Repetição e buffer ---
+// Pras ações do jogo (andar, esperar). Aperto no meio da animação não se perde:
+// fica guardado (só um, o mais recente) e sai assim que o jogador puder agir.
+// Segurando a tecla, depois de repeat_delay a ação repete sozinha.
+#[derive(Debug, Default)]
+pub struct InputBuffer {
+    buffered: Option<Action>, // Apertada enquanto o jogador não podia agir
+    held: Option<Action>,     // Última ação apertada, enquanto a tecla continua segurada
+    held_for: f32,            // Há quanto tempo está segurada
+    since_last: f32,          // Tempo desde a última vez que a ação saiu
+}
+
+impl InputBuffer {
+    // Chamar todo frame. ready = o jogador pode agir agora (não está animando).
+    // Retorna a ação pra executar nesse frame, se tiver.
+    pub fn update(&mut self, bindings: &Bindings, actions: &[Action], dt: f32, ready: bool) -> Option<Action> {
+        let pressed = actions.iter().copied().find(|&action| bindings.pressed(action));
+        let still_held = self.held.is_some_and(|action| bindings.down(action));
+        self.step(bindings, pressed, still_held, dt, ready)
+    }
+
+    // O update sem olhar o teclado: pressed = ação apertada nesse frame,
+    // still_held = a última ação apertada continua segurada.
+    fn step(&mut self, bindings: &Bindings, pressed: Option<Action>, still_held: bool, dt: f32, ready: bool) -> Option<Action> {
+        self.since_last += dt;
+
+        if let Some(action) = pressed {
+            self.buffered = Some(action);
+            self.held = Some(action);
+            self.held_for = 0.0;
+        } else if self.held.is_some() {
+            if still_held {
+                self.held_for += dt;
+            } else {
+                self.held = None;
+            }
+        }
+
+        if !ready {
+            return None;
+        }
+        let action = self.buffered.take().or_else(|| {
+            self.held
+                .filter(|_| self.held_for >= bindings.repeat_delay && self.since_last >= bindings.repeat_interval)
+        })?;
+        self.since_last = 0.0;
+        Some(action)
+    }
+}
+
+fn syntax(line: usize, message: &str) -> InputError {
+    InputError::Syntax { line, message: message.to_owned() }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_overrides_only_what_is_listed() {
+        let bindings = Bindings::parse("# comentário\n\nconfirm  Space,KpEnter\nsave F9\nrepeat_delay 0.5\n").unwrap();
+        assert_eq!(bindings.keys(Action::Confirm), [KeyCode::Space, KeyCode::KpEnter]);
+        assert_eq!(bindings.keys(Action::Save), [KeyCode::F9]);
+        assert_eq!(bindings.keys(Action::Cancel), Action::Cancel.default_keys());
+        assert_eq!(bindings.describe(Action::Confirm), "Space / KpEnter");
+        assert_eq!((bindings.repeat_delay, bindings.repeat_interval), (0.5, DEFAULT_REPEAT_INTERVAL));
+    }
+
+    #[test]
+    fn parse_errors_point_to_the_line() {
+        for text in ["voar Up", "confirm Enter,Foo", "confirm", "confirm Enter Z"] {
+            let error = Bindings::parse(&format!("save F5\n{}", text)).err();
+            assert!(matches!(error, Some(InputError::Syntax { line: 2, .. })), "passou: {}", text);
+        }
+    }
+
+    #[test]
+    fn saved_file_reads_back_the_same() {
+        let mut bindings = Bindings::default();
+        bindings.rebind(Action::Wait, KeyCode::Kp5).unwrap();
+        let text = bindings.to_text();
+        let read = Bindings::parse(&text).unwrap();
+        for action in Action::ALL {
+            assert_eq!(read.keys(action), bindings.keys(action), "{}", action.name());
+        }
+        assert_eq!(read.to_text(), text);
+    }
+
+    #[test]
+    fn defaults_have_no_conflicts() {
+        let bindings = Bindings::default();
+        for action in Action::ALL {
+            for &key in bindings.keys(action) {
+                assert_eq!(bindings.conflict(action, key), None, "{} {:?}", action.name(), key);
+            }
+        }
+    }
+
+    #[test]
+    fn rebind_refuses_keys_taken_in_the_same_context() {
+        let mut bindings = Bindings::default();
+        // F5 é do Salvar, que também é lido no jogo
+        assert_eq!(bindings.rebind(Action::Wait, KeyCode::F5), Err(Action::Save));
+        assert_eq!(bindings.keys(Action::Wait), Action::Wait.default_keys());
+        // Esc volta ao menu no jogo e cancela nos menus: contextos diferentes
+        assert_eq!(bindings.rebind(Action::Cancel, KeyCode::Escape), Ok(()));
+        // Trocar pra uma tecla que a própria ação já tem
+        assert_eq!(bindings.rebind(Action::Save, KeyCode::F5), Ok(()));
+    }
+
+    // Frames de 1/8 s e tempos que são potência de 2, pra conta de float fechar exata
+    const FRAME: f32 = 0.125;
+
+    fn timing() -> Bindings {
+        Bindings { repeat_delay: 0.5, repeat_interval: 0.25, ..Bindings::default() }
+    }
+
+    // Roda os frames (apertada nesse frame, segurada, pode agir) e devolve o que saiu em cada um
+    fn run(frames: &[(Option<Action>, bool, bool)]) -> Vec<Option<Action>> {
+        let bindings = timing();
+        let mut buffer = InputBuffer::default();
+        frames.iter().map(|&(pressed, held, ready)| buffer.step(&bindings, pressed, held, FRAME, ready)).collect()
+    }
+
+    #[test]
+    fn holding_repeats_after_the_delay() {
+        let up = Some(Action::MoveUp);
+        let mut frames = vec![(up, true, true)];
+        frames.extend([(None, true, true); 8]);
+        let out = run(&frames);
+        // Sai na hora, espera 0.5 s (4 frames) segurando, depois repete a cada 0.25 s (2 frames)
+        assert_eq!(out, [up, None, None, None, up, None, up, None, up]);
+    }
+
+    #[test]
+    fn releasing_stops_the_repeat() {
+        let up = Some(Action::MoveUp);
+        let out = run(&[(up, true, true), (None, true, true), (None, false, true), (None, false, true), (None, false, true), (None, false, true)]);
+        assert_eq!(out, [up, None, None, None, None, None]);
+    }
+
+    #[test]
+    fn press_while_busy_comes_out_when_ready() {
+        let wait = Some(Action::Wait);
+        let out = run(&[(wait, false, false), (None, false, false), (None, false, true), (None, false, true)]);
+        assert_eq!(out, [None, None, wait, None]);
+    }
+
+    #[test]
+    fn buffer_keeps_only_the_latest_press() {
+        let (up, left) = (Some(Action::MoveUp), Some(Action::MoveLeft));
+        let out = run(&[(up, false, false), (left, false, false), (None, false, true), (None, false, true)]);
+        assert_eq!(out, [None, None, left, None]);
+    }
+}
@@ -0,0 +1,251 @@
+use macroquad::prelude::*;
+use serde::Deserialize;
+use std::fmt;
+
+use crate::combat::Dice;
+use crate::content::{self, Rarity};
+use crate::loot::DepthRange;
+use crate::equipment::Equip;
+use crate::map::Map;
+use crate::mapgen::FLOOR;
+use crate::rng::Rng;
+use crate::status::StatusEffect;
+
+// --- ITENS ---
+// Tipos de item vêm de assets/items.ron (ver content.rs), igual aos monstros.
+// O glifo identifica o tipo no save e o sprite é um quadro de assets/sprites/items.png.
+// Item no chão fica num tile do andar; pego, vai pro inventário do jogador.
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+pub enum ItemEffect {
+    Heal(Dice),                 // Recupera vida (sem passar do máximo)
+    Status(StatusEffect),       // Efeito em quem usa (poções)
+    StatusInView(StatusEffect), // Efeito em todo monstro que o jogador vê (pergaminhos)
+}
+
+#[derive(Debug)]
+pub struct ItemKind {
+    pub name: &'static str,
+    pub glyph: char,
+    pub sprite: Rect,    // Pedaço do items.png
+    pub stackable: bool, // Vários ocupam uma linha só do inventário
+    pub weight: u32,     // Por unidade
+    pub pile: u32,       // Monte no chão: de 1 até pile * profundidade (1 = sempre um só)
+    pub effect: Option<ItemEffect>, // O que acontece ao usar. None = não dá pra usar
+    pub equip: Option<Equip>,       // Slot e bônus se der pra equipar (ver equipment.rs)
+    pub depth: DepthRange, // Andares em que aparece no gerador sem tabela
+    pub rarity: Rarity,    // Peso no sorteio do gerador
+}
+
+// Quantos itens o gerador espalha sem tabela: ITEMS_BASE + profundidade / 2
+const ITEMS_BASE: usize = 3;
+// Mistura na seed do andar, separada da dos monstros
+const ITEM_SALT: u64 = 0x6974_656D_735F_5F5F;
+
+pub fn kind_for_glyph(glyph: char) -> Option<&'static ItemKind> {
+    content::items().iter().find(|kind| kind.glyph == glyph)
+}
+
+// --- Pilhas ---
+// Um ou mais itens do mesmo tipo. Item não empilhável tem sempre count 1.
+#[derive(Debug, Clone, Copy)]
+pub struct ItemStack {
+    pub kind: &'static ItemKind,
+    pub count: u32,
+}
+
+impl ItemStack {
+    pub fn new(kind: &'static ItemKind, count: u32) -> Self {
+        ItemStack { kind, count }
+    }
+
+    pub fn weight(&self) -> u32 {
+        self.kind.weight * self.count
+    }
+
+    // "Adaga", "Poção de cura x3"
+    pub fn label(&self) -> String {
+        if self.count > 1 {
+            format!("{} x{}", self.kind.name, self.count)
+        } else {
+            self.kind.name.to_owned()
+        }
+    }
+}
+
+// Item largado num tile do andar
+pub struct FloorItem {
+    pub col: usize,
+    pub row: usize,
+    pub stack: ItemStack,
+}
+
+// --- Inventário ---
+// Uma linha por pilha, no máximo INVENTORY_SLOTS (uma letra cada, a-z),
+// e o peso total não passa da capacidade.
+pub const INVENTORY_SLOTS: usize = 26;
+pub const CARRY_CAPACITY: u32 = 50;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PickupError {
+    TooHeavy, // Passaria da capacidade
+    NoRoom,   // Todas as linhas ocupadas
+}
+
+impl fmt::Display for PickupError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PickupError::TooHeavy => write!(f, "pesado demais pra carregar"),
+            PickupError::NoRoom => write!(f, "não cabe mais nada no inventário"),
+        }
+    }
+}
+
+impl std::error::Error for PickupError {}
+
+pub struct Inventory {
+    stacks: Vec<ItemStack>,
+    pub capacity: u32, // Peso máximo
+}
+
+impl Inventory {
+    pub fn new(capacity: u32) -> Self {
+        Inventory { stacks: Vec::new(), capacity }
+    }
+
+    pub fn stacks(&self) -> &[ItemStack] {
+        &self.stacks
+    }
+
+    pub fn weight(&self) -> u32 {
+        self.stacks.iter().map(ItemStack::weight).sum()
+    }
+
+    // Guarda a pilha. Empilhável junta com a do mesmo tipo se tiver.
+    pub fn add(&mut self, stack: ItemStack) -> Result<(), PickupError> {
+        if self.weight() + stack.weight() > self.capacity {
+            return Err(PickupError::TooHeavy);
+        }
+        if stack.kind.stackable
+            && let Some(existing) = self.stacks.iter_mut().find(|s| std::ptr::eq(s.kind, stack.kind))
+        {
+            existing.count += stack.count;
+            return Ok(());
+        }
+        if self.stacks.len() >= INVENTORY_SLOTS {
+            return Err(PickupError::NoRoom);
+        }
+        self.stacks.push(stack);
+        Ok(())
+    }
+
+    // Tira até count itens da linha index. A linha some quando esvazia.
+    pub fn take(&mut self, index: usize, count: u32) -> Option<ItemStack> {
+        let stack = self.stacks.get_mut(index)?;
+        let taken = count.min(stack.count);
+        stack.count -= taken;
+        let kind = stack.kind;
+        if stack.count == 0 {
+            self.stacks.remove(index);
+        }
+        Some(ItemStack::new(kind, taken))
+    }
+}
+
+// --- Spawn ---
+// Itens de um andar novo, espalhados em tiles de chão. O que aparece sai da
+// tabela da profundidade (spawns.ron); sem tabela, ITEMS_BASE + profundidade / 2
+// sorteados pela raridade entre os tipos que cabem na profundidade.
+pub fn spawn_items(map: &Map, depth: u32, seed: u64) -> Vec<FloorItem> {
+    let mut rng = Rng::new(seed ^ ITEM_SALT);
+    let kinds: Vec<&'static ItemKind> = match content::spawns().items.get(depth) {
+        Some(table) => table.roll(&mut rng),
+        None => {
+            let available: Vec<&'static ItemKind> = content::items().iter().filter(|kind| kind.depth.contains(depth)).collect();
+            (0..ITEMS_BASE + depth as usize / 2)
+                .filter_map(|_| rng.pick_weighted(&available, |kind| kind.rarity.weight()).copied())
+                .collect()
+        }
+    };
+    let mut candidates: Vec<(usize, usize)> = map
+        .tiles()
+        .filter(|&(_, _, glyph)| glyph == FLOOR)
+        .map(|(col, row, _)| (col, row))
+        .collect();
+
+    let mut items = Vec::new();
+    for kind in kinds {
+        // Andar pequeno demais: o que sobrou não aparece
+        if candidates.is_empty() {
+            break;
+        }
+        let (col, row) = candidates.swap_remove(rng.range(0, candidates.len()));
+        items.push(FloorItem { col, row, stack: ItemStack::new(kind, roll_amount(kind, depth, &mut rng)) });
+    }
+    items
+}
+
+// Tamanho do monte: de 1 até pile * profundidade
+pub fn roll_amount(kind: &ItemKind, depth: u32, rng: &mut Rng) -> u32 {
+    if kind.pile > 1 { rng.range(1, (kind.pile * depth) as usize + 1) as u32 } else { 1 }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn kind(glyph: char) -> &'static ItemKind {
+        content::tests::load_shipped();
+        kind_for_glyph(glyph).unwrap()
+    }
+
+    #[test]
+    fn stackable_items_merge_into_one_line() {
+        let mut inventory = Inventory::new(CARRY_CAPACITY);
+        inventory.add(ItemStack::new(kind('!'), 2)).unwrap();
+        inventory.add(ItemStack::new(kind('!'), 1)).unwrap();
+        inventory.add(ItemStack::new(kind(')'), 1)).unwrap();
+        inventory.add(ItemStack::new(kind(')'), 1)).unwrap();
+        let lines: Vec<String> = inventory.stacks().iter().map(ItemStack::label).collect();
+        assert_eq!(lines, ["Poção de cura x3", "Adaga", "Adaga"]);
+        assert_eq!(inventory.weight(), 3 * 2 + 2 * 5);
+    }
+
+    #[test]
+    fn weight_limit_refuses_the_whole_stack() {
+        let mut inventory = Inventory::new(10);
+        inventory.add(ItemStack::new(kind(')'), 1)).unwrap();
+        assert_eq!(inventory.add(ItemStack::new(kind('!'), 3)), Err(PickupError::TooHeavy));
+        assert_eq!(inventory.weight(), 5);
+        // Chegar exatamente na capacidade pode
+        inventory.add(ItemStack::new(kind(')'), 1)).unwrap();
+        assert_eq!(inventory.weight(), 10);
+        // Ouro não pesa
+        inventory.add(ItemStack::new(kind('$'), 500)).unwrap();
+    }
+
+    #[test]
+    fn full_inventory_still_merges_stacks() {
+        let mut inventory = Inventory::new(u32::MAX);
+        inventory.add(ItemStack::new(kind('!'), 1)).unwrap();
+        for _ in 1..INVENTORY_SLOTS {
+            inventory.add(ItemStack::new(kind(')'), 1)).unwrap();
+        }
+        assert_eq!(inventory.add(ItemStack::new(kind(')'), 1)), Err(PickupError::NoRoom));
+        inventory.add(ItemStack::new(kind('!'), 1)).unwrap();
+        assert_eq!(inventory.stacks().len(), INVENTORY_SLOTS);
+        assert_eq!(inventory.stacks()[0].count, 2);
+    }
+
+    #[test]
+    fn take_splits_and_empties_lines() {
+        let mut inventory = Inventory::new(CARRY_CAPACITY);
+        inventory.add(ItemStack::new(kind('$'), 7)).unwrap();
+        inventory.add(ItemStack::new(kind(')'), 1)).unwrap();
+        assert_eq!(inventory.take(0, 3).map(|s| s.count), Some(3));
+        assert_eq!(inventory.stacks()[0].count, 4);
+        // Pedir mais do que tem leva o que tem e a linha some
+        assert_eq!(inventory.take(0, 10).map(|s| s.count), Some(4));
+        assert_eq!(inventory.stacks().len(), 1);
+        assert!(inventory.take(5, 1).is_none());
+    }
+}
@@ -0,0 +1,140 @@
+use crate::combat::Stats;
+
+// --- NÍVEIS ---
+// O jogador ganha XP com cada monstro que morre (xp do tipo, em monsters.ron).
+// O XP é o total do jogo todo; passou de next_level_xp, sobe de nível:
+// ganha LEVEL_HP de vida máxima na hora (e cura o mesmo tanto) e um ponto de
+// vantagem, que pode gastar quando quiser na tela de personagem.
+//
+//   nível 2 com 15 XP, 3 com 45, 4 com 90, 5 com 150...
+const XP_STEP: u32 = 15;
+pub const LEVEL_HP: i32 = 4;
+
+// XP total pra passar do nível level pro próximo
+pub fn next_level_xp(level: u32) -> u32 {
+    XP_STEP * level * (level + 1) / 2
+}
+
+// Vantagens que o jogador escolhe ao subir de nível. Mexem nos atributos base,
+// então ficam no save junto com eles.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Perk {
+    Strength,
+    Toughness,
+    Vitality,
+    Sight,
+}
+
+const PERK_HP: i32 = 6; // Vigor dá mais que o LEVEL_HP normal
+
+impl Perk {
+    // Ordem da tela de personagem
+    pub const ALL: [Perk; 4] = [Perk::Strength, Perk::Toughness, Perk::Vitality, Perk::Sight];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Perk::Strength => "Força",
+            Perk::Toughness => "Couro grosso",
+            Perk::Vitality => "Vigor",
+            Perk::Sight => "Olhar atento",
+        }
+    }
+
+    pub fn describe(self) -> String {
+        match self {
+            Perk::Strength => "+1 ataque".to_owned(),
+            Perk::Toughness => "+1 defesa".to_owned(),
+            Perk::Vitality => format!("+{} vida máxima", PERK_HP),
+            Perk::Sight => "+1 visão".to_owned(),
+        }
+    }
+
+    // Aplica nos atributos base do jogador
+    pub fn apply(self, stats: &mut Stats, fov_radius: &mut usize) {
+        match self {
+            Perk::Strength => stats.attack += 1,
+            Perk::Toughness => stats.defense += 1,
+            Perk::Vitality => {
+                stats.max_hp += PERK_HP;
+                stats.hp += PERK_HP;
+            }
+            Perk::Sight => *fov_radius += 1,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Experience {
+    pub level: u32,
+    pub xp: u32,          // Total, não zera ao subir
+    pub perk_points: u32, // Vantagens ganhas e ainda não escolhidas
+}
+
+impl Default for Experience {
+    fn default() -> Self {
+        Experience { level: 1, xp: 0, perk_points: 0 }
+    }
+}
+
+impl Experience {
+    // Soma o XP e devolve quantos níveis subiu (um monstro forte pode dar mais de um)
+    pub fn gain(&mut self, xp: u32) -> u32 {
+        self.xp += xp;
+        let mut levels = 0;
+        while self.xp >= next_level_xp(self.level) {
+            self.level += 1;
+            self.perk_points += 1;
+            levels += 1;
+        }
+        levels
+    }
+
+    // XP total do próximo nível
+    pub fn next(&self) -> u32 {
+        next_level_xp(self.level)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::combat::Dice;
+
+    #[test]
+    fn curve_matches_the_comment_and_keeps_growing() {
+        assert_eq!([1, 2, 3, 4].map(next_level_xp), [15, 45, 90, 150]);
+        for level in 1..100 {
+            assert!(next_level_xp(level + 1) > next_level_xp(level), "nível {}", level);
+        }
+    }
+
+    #[test]
+    fn gain_crosses_one_level() {
+        let mut experience = Experience::default();
+        assert_eq!(experience.gain(14), 0);
+        assert_eq!(experience.gain(1), 1);
+        assert_eq!(experience, Experience { level: 2, xp: 15, perk_points: 1 });
+        assert_eq!(experience.next(), 45);
+    }
+
+    #[test]
+    fn gain_crosses_several_levels_at_once() {
+        let mut experience = Experience::default();
+        assert_eq!(experience.gain(100), 3);
+        assert_eq!(experience, Experience { level: 4, xp: 100, perk_points: 3 });
+        // Os pontos acumulam com os que ainda não foram gastos
+        assert_eq!(experience.gain(50), 1);
+        assert_eq!((experience.level, experience.perk_points), (5, 4));
+    }
+
+    #[test]
+    fn perks_change_the_base_stats() {
+        let mut stats = Stats::new(10, 3, 12, Dice::new(1, 6, 0));
+        let mut fov_radius = 8;
+        for perk in Perk::ALL {
+            perk.apply(&mut stats, &mut fov_radius);
+        }
+        assert_eq!((stats.hp, stats.max_hp, stats.attack, stats.defense), (16, 16, 4, 13));
+        assert_eq!(fov_radius, 9);
+    }
+}
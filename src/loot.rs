@@ -0,0 +1,287 @@
+use serde::Deserialize;
+use std::fmt;
+
+use crate::rng::Rng;
+
+// --- TABELAS DE SORTEIO ---
+// Tabela com pesos: cada rolagem escolhe uma das entries com chance
+// proporcional ao peso. A entrada sorteada pode ser um valor, outra tabela
+// (que rola do jeito dela) ou nada. O que está em always sai sempre, antes
+// das rolagens. Não sabe o que está sorteando (T): os arquivos trazem glifos
+// e o content.rs troca pelos tipos com resolve. Mesma seed no Rng = mesmo resultado.
+//
+//   (rolls: 2, always: [One('$')], entries: [(3, One('!')), (1, Table((entries: ...))), (6, Nothing)])
+#[derive(Debug, Clone, Deserialize)]
+pub enum Pick<T> {
+    One(T),
+    Table(Table<T>),
+    Nothing,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields, bound = "T: Deserialize<'de>")]
+pub struct Table<T> {
+    #[serde(default = "one_roll")]
+    pub rolls: u32, // Quantas vezes sorteia nas entries
+    #[serde(default)]
+    pub always: Vec<Pick<T>>, // Garantidos
+    #[serde(default)]
+    pub entries: Vec<(u32, Pick<T>)>, // (peso, o que sai)
+}
+
+fn one_roll() -> u32 {
+    1
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LootError {
+    Empty,      // Sem always nem entries, nunca sai nada
+    ZeroWeight, // Tem entries mas os pesos somam zero
+}
+
+impl fmt::Display for LootError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LootError::Empty => write!(f, "tabela vazia (sem always nem entries)"),
+            LootError::ZeroWeight => write!(f, "os pesos das entries somam zero"),
+        }
+    }
+}
+
+impl std::error::Error for LootError {}
+
+impl<T: Clone> Pick<T> {
+    fn roll_into(&self, rng: &mut Rng, out: &mut Vec<T>) {
+        match self {
+            Pick::One(value) => out.push(value.clone()),
+            Pick::Table(table) => table.roll_into(rng, out),
+            Pick::Nothing => {}
+        }
+    }
+}
+
+impl<T: Clone> Table<T> {
+    // Tudo que saiu, garantidos primeiro
+    pub fn roll(&self, rng: &mut Rng) -> Vec<T> {
+        let mut out = Vec::new();
+        self.roll_into(rng, &mut out);
+        out
+    }
+
+    fn roll_into(&self, rng: &mut Rng, out: &mut Vec<T>) {
+        for pick in &self.always {
+            pick.roll_into(rng, out);
+        }
+        for _ in 0..self.rolls {
+            if let Some((_, pick)) = rng.pick_weighted(&self.entries, |(weight, _)| *weight) {
+                pick.roll_into(rng, out);
+            }
+        }
+    }
+}
+
+impl<T> Pick<T> {
+    fn resolve<U, E>(self, f: &mut impl FnMut(T) -> Result<U, E>) -> Result<Pick<U>, E> {
+        Ok(match self {
+            Pick::One(value) => Pick::One(f(value)?),
+            Pick::Table(table) => Pick::Table(table.resolve(f)?),
+            Pick::Nothing => Pick::Nothing,
+        })
+    }
+
+    fn check(&self) -> Result<(), LootError> {
+        match self {
+            Pick::Table(table) => table.check(),
+            Pick::One(_) | Pick::Nothing => Ok(()),
+        }
+    }
+}
+
+impl<T> Table<T> {
+    // Mesma tabela com cada valor trocado por f(valor). Para no primeiro erro.
+    pub fn resolve<U, E>(self, f: &mut impl FnMut(T) -> Result<U, E>) -> Result<Table<U>, E> {
+        let always = self.always.into_iter().map(|pick| pick.resolve(f)).collect::<Result<_, _>>()?;
+        let entries = self
+            .entries
+            .into_iter()
+            .map(|(weight, pick)| Ok((weight, pick.resolve(f)?)))
+            .collect::<Result<_, _>>()?;
+        Ok(Table { rolls: self.rolls, always, entries })
+    }
+
+    // Confere essa e as de dentro
+    pub fn check(&self) -> Result<(), LootError> {
+        if self.always.is_empty() && self.entries.is_empty() {
+            return Err(LootError::Empty);
+        }
+        if !self.entries.is_empty() && self.entries.iter().all(|(weight, _)| *weight == 0) {
+            return Err(LootError::ZeroWeight);
+        }
+        self.always.iter().chain(self.entries.iter().map(|(_, pick)| pick)).try_for_each(Pick::check)
+    }
+}
+
+// --- Por profundidade ---
+// Andares de uma faixa. Sem max = daí pra baixo.
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct DepthRange {
+    pub min: u32,
+    #[serde(default)]
+    pub max: Option<u32>,
+}
+
+impl DepthRange {
+    pub fn contains(&self, depth: u32) -> bool {
+        depth >= self.min && self.max.is_none_or(|max| depth <= max)
+    }
+
+    pub fn overlaps(&self, other: &DepthRange) -> bool {
+        self.max.is_none_or(|max| other.min <= max) && other.max.is_none_or(|max| self.min <= max)
+    }
+}
+
+// "1-3", "5+"
+impl fmt::Display for DepthRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.max {
+            Some(max) if max == self.min => write!(f, "{}", self.min),
+            Some(max) => write!(f, "{}-{}", self.min, max),
+            None => write!(f, "{}+", self.min),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Band<T> {
+    pub depth: DepthRange,
+    pub table: Table<T>,
+}
+
+// Uma tabela por faixa de profundidade. As faixas não se cruzam (ver content.rs).
+#[derive(Debug, Clone, Deserialize)]
+#[serde(transparent)]
+pub struct DepthTable<T> {
+    pub bands: Vec<Band<T>>,
+}
+
+impl<T> DepthTable<T> {
+    // Tabela da faixa que tem a profundidade. None = nenhuma faixa cobre.
+    pub fn get(&self, depth: u32) -> Option<&Table<T>> {
+        self.bands.iter().find(|band| band.depth.contains(depth)).map(|band| &band.table)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ron::extensions::Extensions;
+
+    // Mesmo jeito que o content.rs lê (Option sem Some)
+    fn parse<T: serde::de::DeserializeOwned>(text: &str) -> T {
+        ron::Options::default().with_default_extension(Extensions::IMPLICIT_SOME).from_str(text).unwrap()
+    }
+
+    fn count(picks: &[char], glyph: char) -> usize {
+        picks.iter().filter(|&&pick| pick == glyph).count()
+    }
+
+    #[test]
+    fn same_seed_same_picks() {
+        let table: Table<char> = parse("(rolls: 5, entries: [(3, One('a')), (2, One('b')), (1, Table((entries: [(1, One('c'))])))])");
+        for seed in [0, 7, 123_456] {
+            let (mut r1, mut r2) = (Rng::new(seed), Rng::new(seed));
+            for _ in 0..20 {
+                assert_eq!(table.roll(&mut r1), table.roll(&mut r2));
+            }
+        }
+    }
+
+    #[test]
+    fn always_comes_first_every_time() {
+        let table: Table<char> = parse("(rolls: 2, always: [One('$'), One('!')], entries: [(1, One('a')), (1, Nothing)])");
+        let mut rng = Rng::new(1);
+        for _ in 0..100 {
+            let picks = table.roll(&mut rng);
+            assert_eq!(&picks[..2], &['$', '!']);
+            assert!(picks.len() <= 4);
+        }
+        // Só always, sem entries: sai exatamente ele
+        let only: Table<char> = parse("(always: [One('$')])");
+        assert_eq!(only.roll(&mut rng), vec!['$']);
+    }
+
+    #[test]
+    fn nothing_and_nested_tables() {
+        // Metade das vezes nada, metade um bando de 3
+        let table: Table<char> = parse("(entries: [(1, Nothing), (1, Table((rolls: 3, entries: [(1, One('g'))])))])");
+        let mut rng = Rng::new(42);
+        let mut empty = 0;
+        for _ in 0..1000 {
+            let picks = table.roll(&mut rng);
+            match picks.len() {
+                0 => empty += 1,
+                3 => assert_eq!(count(&picks, 'g'), 3),
+                n => panic!("saíram {} itens", n),
+            }
+        }
+        assert!((400..600).contains(&empty), "{} vazios", empty);
+    }
+
+    #[test]
+    fn weights_are_respected() {
+        let table: Table<char> = parse("(rolls: 1000, entries: [(3, One('a')), (1, One('b')), (0, One('z'))])");
+        let picks = table.roll(&mut Rng::new(5));
+        assert_eq!(count(&picks, 'z'), 0);
+        assert!((700..800).contains(&count(&picks, 'a')), "{} a", count(&picks, 'a'));
+    }
+
+    #[test]
+    fn check_rejects_empty_and_zero_weight() {
+        let empty: Table<char> = parse("()");
+        assert_eq!(empty.check(), Err(LootError::Empty));
+        let zero: Table<char> = parse("(entries: [(0, One('a')), (0, Nothing)])");
+        assert_eq!(zero.check(), Err(LootError::ZeroWeight));
+        // Tabela de dentro também é conferida
+        let nested: Table<char> = parse("(always: [One('a')], entries: [(1, Table(()))])");
+        assert_eq!(nested.check(), Err(LootError::Empty));
+        let weightless: Table<char> = parse("(always: [One('a')], entries: [(0, One('b'))])");
+        assert_eq!(weightless.check(), Err(LootError::ZeroWeight));
+        let ok: Table<char> = parse("(always: [One('a')])");
+        assert_eq!(ok.check(), Ok(()));
+    }
+
+    #[test]
+    fn resolve_stops_at_unknown_value() {
+        let table: Table<char> = parse("(always: [One('a')], entries: [(1, Table((entries: [(1, One('?'))])))])");
+        let known = |glyph: char| if glyph == 'a' { Ok(1) } else { Err(glyph) };
+        assert_eq!(table.clone().resolve(&mut |glyph| known(glyph)).err(), Some('?'));
+
+        let resolved = table.resolve(&mut |glyph| Ok::<_, ()>(glyph as u32)).unwrap();
+        assert_eq!(resolved.roll(&mut Rng::new(3)), vec!['a' as u32, '?' as u32]);
+    }
+
+    #[test]
+    fn depth_ranges() {
+        let closed = DepthRange { min: 2, max: Some(4) };
+        let open = DepthRange { min: 5, max: None };
+        assert!(!closed.contains(1) && closed.contains(2) && closed.contains(4) && !closed.contains(5));
+        assert!(open.contains(5) && open.contains(1000) && !open.contains(4));
+        assert!(!closed.overlaps(&open) && !open.overlaps(&closed));
+        assert!(closed.overlaps(&DepthRange { min: 4, max: Some(9) }));
+        assert!(open.overlaps(&DepthRange { min: 1, max: None }));
+        assert_eq!(format!("{} {} {}", closed, open, DepthRange { min: 3, max: Some(3) }), "2-4 5+ 3");
+    }
+
+    #[test]
+    fn depth_table_picks_the_band() {
+        let table: DepthTable<char> = parse(
+            "[(depth: (min: 1, max: 2), table: (always: [One('a')])), (depth: (min: 4), table: (always: [One('b')]))]",
+        );
+        let mut rng = Rng::new(0);
+        assert_eq!(table.get(2).unwrap().roll(&mut rng), vec!['a']);
+        assert!(table.get(3).is_none());
+        assert_eq!(table.get(40).unwrap().roll(&mut rng), vec!['b']);
+    }
+}
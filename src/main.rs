@@ -1,6 +1,44 @@
 use macroquad::prelude::*;
+use std::collections::HashMap;
+use std::error::Error;
 use std::process::exit; // Importar a função exit
 
+mod combat;
+mod content;
+mod dungeon;
+mod equipment;
+mod fov;
+mod game;
+mod gamepad;
+mod input;
+mod item;
+mod level;
+mod loot;
+mod map;
+mod mapgen;
+mod monster;
+mod motion;
+mod path;
+mod player;
+mod rng;
+mod save;
+mod status;
+mod tiles;
+mod turn;
+use dungeon::{Dungeon, Floor};
+use game::Run;
+use gamepad::Gamepads;
+use input::{Action, Bindings, InputBuffer};
+use equipment::EquipSlot;
+use level::Perk;
+use item::ItemStack;
+use status::Statuses;
+use mapgen::{LayoutTable, STAIRS_DOWN, STAIRS_UP};
+use player::Player;
+use save::SlotStatus;
+use tiles::TileRegistry;
+use turn::Command;
+
 // --- CONST ---
 // --- setup macroquad---
 const SCREEN_WIDTH: f32 = 1024.0;
@@ -12,30 +50,57 @@ const FADE_DURATION: f32 = 0.8;  //Talvez em outras partes? ->FADE
 const SPLASH_COUNT: usize = 5;
 // --- game room ---
 const TILE_SIZE: f32 = 128.0;
-const MAP_WIDTH: usize = 16; 
-const MAP_HEIGHT: usize = 5;
-// Definição da sala
-// x = muro
-// o = chão
-// s = spawn
-const MAP_DATA: [&str; MAP_HEIGHT] = [
-    "xxxxx     xxxxxx",
-    "xoooxxxxxxxoooox",
-    "xsooooooooooooox",
-    "xoooxxxxxxxooosx",
-    "xxxxx     xxxxxx",
-];
+// Layout da masmorra (formato no mapgen.rs) e tabela de tiles (formato no tiles.rs)
+const DUNGEON_CONFIG: &str = "dungeon.cfg";
+const TILE_TABLE: &str = "tiles.cfg";
+// --- visão ---
+const DEFAULT_FOV_RADIUS: usize = 8; // Raio de visão do jogador, em tiles
+const REMEMBERED_TILE_COLOR: Color = Color::new(0.35, 0.35, 0.45, 1.0); // Tiles explorados fora de vista
+const CORPSE_COLOR: Color = Color::new(0.4, 0.25, 0.25, 0.8); // Cadáver = sprite do monstro escurecido
+// --- HUD ---
+const LOG_LINES: usize = 4; // Quantas mensagens de combate aparecem na tela
+const INVENTORY_PANEL: Rect = Rect { x: 162.0, y: 60.0, w: 700.0, h: 640.0 }; // Janela do inventário, por cima do jogo
+const INVENTORY_ROWS: usize = 12; // Linhas visíveis da mochila, o resto rola junto com a seleção
+const CHARACTER_PANEL: Rect = Rect { x: 162.0, y: 120.0, w: 700.0, h: 460.0 }; // Janela de personagem
 // --- anim ---
 const MOVE_DURATION: f32 = 0.15; // Duração da animação de movimento (em segundos)
 
+// --- const input ---
+// Ações que viram turno dentro do jogo (passam pelo InputBuffer)
+const GAME_ACTIONS: [Action; 9] = [
+    Action::MoveRight,
+    Action::MoveLeft,
+    Action::MoveDown,
+    Action::MoveUp,
+    Action::MoveUpLeft,
+    Action::MoveUpRight,
+    Action::MoveDownLeft,
+    Action::MoveDownRight,
+    Action::Wait,
+];
+
 // --- const menu ---
-const MENU_OPTIONS_COUNT: i32 = 3;
+// New/Load/Exit estão desenhados no main.png. Continuar é texto por cima
+// e só aparece quando existe autosave.
+const MENU_OPTIONS: [MenuOption; 4] = [MenuOption::Continue, MenuOption::NewGame, MenuOption::Load, MenuOption::Exit];
 // Posições X, Y para o arrow.png
-const MENU_POSITIONS: [(f32, f32); MENU_OPTIONS_COUNT as usize] = [
+const MENU_POSITIONS: [(f32, f32); MENU_OPTIONS.len()] = [
+    (640.0, 415.0), // Continue
     (640.0, 495.0), // New
     (640.0, 575.0), // Load
     (640.0, 655.0), // Exit
 ];
+// Área clicável de cada opção, a partir da seta: a seta e o texto ao lado
+const MENU_ITEM_OFFSET_Y: f32 = -10.0;
+const MENU_ITEM_SIZE: (f32, f32) = (340.0, 80.0);
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum MenuOption {
+    Continue,
+    NewGame,
+    Load,
+    Exit,
+}
 
 // --- States ---
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -44,30 +109,24 @@ enum GameState {
     MainScreen, //Mainscreen
     InGame, // No dungeon
     Load,   // Tela de load 
-}
-
-// --- Player ---
-struct Player {
-    // Posição x/y
-    x: f32, 
-    y: f32, 
-
-    start_x: f32, // Posição X de onde o movimento começou
-    start_y: f32, // Posição Y de onde o movimento começou
-    target_x: f32, // Posição X para onde o jogador está indo
-    target_y: f32, // Posição Y para onde o jogador está indo
-
-    move_timer: f32, // Contador de tempo para a animação de movimento (restante)
+    GameOver, // Jogador morreu
+    Controls, // Tela de controles, aberta de dentro do jogo
+    Inventory, // Inventário aberto por cima do jogo (jogo pausado)
+    Character, // Nível, atributos e vantagens, por cima do jogo (jogo pausado)
 }
 
 // --- ASSETS ---
 struct GameAssets {
     splash_textures: [Texture2D; SPLASH_COUNT],
     main_screen_texture: Texture2D,
-    tileset_texture: Texture2D,     // tileset
+    tilesets: HashMap<String, Texture2D>, // tilesets já carregados, pelo caminho
     selector_texture: Texture2D,    // arrow.png
     load_screen_texture: Texture2D, // BG loadscreen
     player_texture: Texture2D,      // Player.png
+    monster_texture: Texture2D,     // monsters.png, um quadro por tipo de monstro
+    item_texture: Texture2D,        // items.png, um quadro por tipo de item
+    equipment_texture: Texture2D,   // equipment.png, camadas por cima do jogador
+    status_texture: Texture2D,      // status.png, ícones dos efeitos no HUD
 }
 
 impl GameAssets {
@@ -86,9 +145,7 @@ impl GameAssets {
         let main_screen_texture = load_texture("BG/main.png").await?;
         main_screen_texture.set_filter(FilterMode::Linear);
 
-        // 3. Carrega tileset
-        let tileset_texture = load_texture("dng/map1.png").await?;
-        tileset_texture.set_filter(FilterMode::Nearest);
+        // 3. Tilesets dependem do mapa, são carregados no load_tileset
 
         // 4. Carrega o seletor do menu
         let selector_texture = load_texture("arrow.png").await?;
@@ -102,6 +159,21 @@ impl GameAssets {
         let player_texture = load_texture("sprites/Player.png").await?;
         player_texture.set_filter(FilterMode::Nearest); // Nearest para pixel art
 
+        // 7. Carrega os sprites dos monstros
+        let monster_texture = load_texture("sprites/monsters.png").await?;
+        monster_texture.set_filter(FilterMode::Nearest);
+
+        // 8. Carrega os sprites dos itens
+        let item_texture = load_texture("sprites/items.png").await?;
+        item_texture.set_filter(FilterMode::Nearest);
+
+        // 9. Carrega as camadas de equipamento do jogador
+        let equipment_texture = load_texture("sprites/equipment.png").await?;
+        equipment_texture.set_filter(FilterMode::Nearest);
+
+        // 10. Carrega os ícones de efeito
+        let status_texture = load_texture("sprites/status.png").await?;
+        status_texture.set_filter(FilterMode::Nearest);
 
         // Converte o Vec para Array
         let splash_textures_array: [Texture2D; SPLASH_COUNT] = splash_textures
@@ -111,64 +183,421 @@ impl GameAssets {
         Ok(GameAssets {
             splash_textures: splash_textures_array,
             main_screen_texture,
-            tileset_texture, 
+            tilesets: HashMap::new(),
             selector_texture,   
             load_screen_texture,
             player_texture, // Adicionado aqui
+            monster_texture,
+            item_texture,
+            equipment_texture,
+            status_texture,
         })
     }
+
+    // Carrega o tileset de um mapa, se ainda não estiver carregado.
+    async fn load_tileset(&mut self, path: &str) -> Result<(), macroquad::Error> {
+        if !self.tilesets.contains_key(path) {
+            let texture = load_texture(path).await?;
+            texture.set_filter(FilterMode::Nearest);
+            self.tilesets.insert(path.to_owned(), texture);
+        }
+        Ok(())
+    }
+
+    fn tileset(&self, path: &str) -> &Texture2D {
+        self.tilesets.get(path).expect("Tileset deve ter sido carregado no load_tileset")
+    }
 }
 
 // --- FUNÇÕES AUXILIARES --
 // Nova função para desenhar o mapa
-fn draw_map(tileset: &Texture2D) {
-    for (row_idx, row_str) in MAP_DATA.iter().enumerate() {
-        for (col_idx, tile_char) in row_str.chars().enumerate() {
-            // Define de qual parte do tileset vamos cortar o tile
-            let source_rect = match tile_char {
-                // 'x' (muro): (0, 0)
-                'x' => Some(Rect::new(0.0, 0.0, TILE_SIZE, TILE_SIZE)),
-                // 'o' (chão): (128, 0) do spritesheet
-                'o' => Some(Rect::new(TILE_SIZE, 0.0, TILE_SIZE, TILE_SIZE)),
-                // 's' (chão): idêntico ao tile de chão básico, serve como marcador de spawn.
-                's' => Some(Rect::new(TILE_SIZE, 0.0, TILE_SIZE, TILE_SIZE)),
-                // Qualquer outro caractere: não desenha nada
-                _ => None,
-            };
-
-            if let Some(rect) = source_rect {
-                draw_texture_ex(
-                    tileset,
-                    col_idx as f32 * TILE_SIZE, // Posição X na tela
-                    row_idx as f32 * TILE_SIZE, // Posição Y na tela
-                    WHITE,
-                    DrawTextureParams {
-                        source: Some(rect), // A mágica acontece aqui!
-                        ..Default::default()
-                    },
-                );
-            }
+// Com névoa de guerra: visível normal, explorado escurecido, resto não desenha.
+fn draw_map(floor: &Floor, registry: &TileRegistry, tileset: &Texture2D) {
+    for (col_idx, row_idx, tile_char) in floor.map.tiles() {
+        let tint = if floor.visible.get(col_idx, row_idx) {
+            WHITE
+        } else if floor.explored.get(col_idx, row_idx) {
+            REMEMBERED_TILE_COLOR
+        } else {
+            continue; // Nunca visto
+        };
+
+        // A tabela de tiles diz de qual parte do tileset vamos cortar o tile.
+        // Vazio (' ') não está na tabela: não desenha nada
+        if let Some(def) = registry.get(tile_char) {
+            draw_texture_ex(
+                tileset,
+                col_idx as f32 * TILE_SIZE, // Posição X na tela
+                row_idx as f32 * TILE_SIZE, // Posição Y na tela
+                tint,
+                DrawTextureParams {
+                    source: Some(def.source), // A mágica acontece aqui!
+                    ..Default::default()
+                },
+            );
         }
     }
 }
 
-// Obtém o caractere do tile em uma posição de pixel (x, y) do mundo.
-// Retorna ' ' se estiver fora dos limites.
-fn get_tile_char(x: f32, y: f32) -> char {
-    // Converte a posição em pixels para coordenadas de tile (coluna/linha)
-    // Usamos floor para mapear o pixel (centro do jogador) para o tile correto
-    // Ajustamos para TILE_SIZE para garantir que pegamos o tile de destino
-    let col = (x / TILE_SIZE).floor() as isize;
-    let row = (y / TILE_SIZE).floor() as isize;
-
-    if row >= 0 && row < MAP_HEIGHT as isize && col >= 0 && col < MAP_WIDTH as isize {
-        // Pega o caractere do mapa. A indexação é segura por causa do if
-        MAP_DATA[row as usize].chars().nth(col as usize).unwrap_or(' ')
-    } else {
-        ' ' // Fora do limite
+// Monstros só aparecem nos tiles que o jogador está vendo agora.
+// Cadáveres primeiro, pra ficarem por baixo de quem está vivo.
+fn draw_monsters(floor: &Floor, texture: &Texture2D) {
+    for corpse in floor.corpses.iter().filter(|c| floor.visible.get(c.col, c.row)) {
+        let (x, y) = tile_center(corpse.col, corpse.row);
+        draw_texture_ex(
+            texture,
+            x - TILE_SIZE / 2.0,
+            y - TILE_SIZE / 2.0,
+            CORPSE_COLOR,
+            DrawTextureParams {
+                source: Some(corpse.kind.sprite),
+                rotation: std::f32::consts::FRAC_PI_2, // Deitado
+                ..Default::default()
+            },
+        );
+    }
+    for monster in floor.monsters.iter().filter(|m| floor.visible.get(m.col, m.row)) {
+        draw_texture_ex(
+            texture,
+            monster.motion.x - TILE_SIZE / 2.0,
+            monster.motion.y - TILE_SIZE / 2.0,
+            WHITE,
+            DrawTextureParams {
+                source: Some(monster.source()),
+                ..Default::default()
+            },
+        );
+    }
+}
+
+// Itens no chão: visíveis normais, lembrados (explorado fora de vista) escurecidos.
+// Num tile com vários, desenha na ordem e o último fica por cima.
+fn draw_items(floor: &Floor, texture: &Texture2D) {
+    for floor_item in &floor.items {
+        let tint = if floor.visible.get(floor_item.col, floor_item.row) {
+            WHITE
+        } else if floor.explored.get(floor_item.col, floor_item.row) {
+            REMEMBERED_TILE_COLOR
+        } else {
+            continue;
+        };
+        draw_texture_ex(
+            texture,
+            floor_item.col as f32 * TILE_SIZE,
+            floor_item.row as f32 * TILE_SIZE,
+            tint,
+            DrawTextureParams {
+                source: Some(floor_item.stack.kind.sprite),
+                ..Default::default()
+            },
+        );
+    }
+}
+
+// Nome do item com o que ele muda, se for equipamento
+fn item_line(stack: &ItemStack) -> String {
+    match &stack.kind.equip {
+        Some(equip) => format!("{} ({})", stack.label(), equip.bonus.describe()),
+        None => stack.label(),
+    }
+}
+
+// Janela do inventário (coordenadas de tela). Seleção: primeiro os slots de
+// equipamento (EquipSlot::ALL), depois a mochila, uma letra por linha.
+fn draw_inventory(player: &Player, selection: usize, bindings: &Bindings) {
+    let panel = INVENTORY_PANEL;
+    draw_rectangle(panel.x, panel.y, panel.w, panel.h, Color::new(0.0, 0.0, 0.0, 0.85));
+    draw_rectangle_lines(panel.x, panel.y, panel.w, panel.h, 2.0, LIGHTGRAY);
+    draw_text("EQUIPAMENTO", panel.x + 20.0, panel.y + 40.0, 32.0, YELLOW);
+
+    for (i, slot) in EquipSlot::ALL.iter().enumerate() {
+        let y = panel.y + 80.0 + i as f32 * 28.0;
+        let color = if i == selection { YELLOW } else { WHITE };
+        let worn = player.equipment.get(*slot).map_or("-".to_owned(), item_line);
+        draw_text(&format!("{}: {}", slot.label(), worn), panel.x + 20.0, y, 26.0, color);
+    }
+
+    let stats = player.effective_stats();
+    draw_text(
+        &format!("Ataque {} | Defesa {} | Visão {} | Velocidade {}", stats.attack, stats.defense, player.fov(), player.energy.speed),
+        panel.x + 20.0,
+        panel.y + 80.0 + EquipSlot::ALL.len() as f32 * 28.0,
+        22.0,
+        LIME,
+    );
+
+    let inventory = &player.inventory;
+    let bag_y = panel.y + 250.0;
+    draw_text("MOCHILA", panel.x + 20.0, bag_y, 32.0, YELLOW);
+    let stacks = inventory.stacks();
+    if stacks.is_empty() {
+        draw_text("Nada aqui.", panel.x + 20.0, bag_y + 40.0, 26.0, LIGHTGRAY);
+    }
+    // Rola pra seleção sempre aparecer
+    let selected = selection.checked_sub(EquipSlot::ALL.len());
+    let first = selected.unwrap_or(0).saturating_sub(INVENTORY_ROWS - 1);
+    for (i, stack) in stacks.iter().enumerate().skip(first).take(INVENTORY_ROWS) {
+        let y = bag_y + 40.0 + (i - first) as f32 * 28.0;
+        let color = if Some(i) == selected { YELLOW } else { WHITE };
+        let letter = (b'a' + i as u8) as char;
+        draw_text(&format!("{}) {}", letter, item_line(stack)), panel.x + 20.0, y, 26.0, color);
+        draw_text(&format!("{}", stack.weight()), panel.x + panel.w - 60.0, y, 26.0, color);
+    }
+
+    draw_text(
+        &format!("Peso {}/{}", inventory.weight(), inventory.capacity),
+        panel.x + 20.0,
+        panel.y + panel.h - 50.0,
+        24.0,
+        LIME,
+    );
+    draw_text(
+        &format!(
+            "{} usa / equipa / tira, {} larga, {} fecha",
+            bindings.describe(Action::Confirm),
+            bindings.describe(Action::Drop),
+            bindings.describe(Action::Cancel)
+        ),
+        panel.x + 20.0,
+        panel.y + panel.h - 20.0,
+        22.0,
+        WHITE,
+    );
+}
+
+// Tela de personagem: nível, atributos com bônus e as vantagens pra escolher
+fn draw_character(player: &Player, selection: usize, bindings: &Bindings) {
+    let panel = CHARACTER_PANEL;
+    draw_rectangle(panel.x, panel.y, panel.w, panel.h, Color::new(0.0, 0.0, 0.0, 0.85));
+    draw_rectangle_lines(panel.x, panel.y, panel.w, panel.h, 2.0, LIGHTGRAY);
+    draw_text("PERSONAGEM", panel.x + 20.0, panel.y + 40.0, 32.0, YELLOW);
+
+    let experience = &player.experience;
+    draw_text(
+        &format!("Nível {} | XP {} (próximo nível com {})", experience.level, experience.xp, experience.next()),
+        panel.x + 20.0,
+        panel.y + 80.0,
+        26.0,
+        WHITE,
+    );
+    let stats = player.effective_stats();
+    draw_text(
+        &format!(
+            "Vida {}/{} | Ataque {} | Defesa {} | Visão {} | Velocidade {}",
+            stats.hp, stats.max_hp, stats.attack, stats.defense, player.fov(), player.energy.speed
+        ),
+        panel.x + 20.0,
+        panel.y + 110.0,
+        22.0,
+        LIME,
+    );
+
+    let perks_y = panel.y + 170.0;
+    let title = match experience.perk_points {
+        0 => "VANTAGENS (suba de nível pra ganhar)".to_owned(),
+        points => format!("VANTAGENS ({} pra escolher)", points),
+    };
+    draw_text(&title, panel.x + 20.0, perks_y, 28.0, YELLOW);
+    for (i, perk) in Perk::ALL.iter().enumerate() {
+        let y = perks_y + 40.0 + i as f32 * 30.0;
+        let color = match (experience.perk_points > 0, i == selection) {
+            (true, true) => YELLOW,
+            (true, false) => WHITE,
+            (false, _) => GRAY,
+        };
+        draw_text(&format!("{}: {}", perk.label(), perk.describe()), panel.x + 20.0, y, 26.0, color);
+    }
+
+    draw_text(
+        &format!("{} escolhe, {} fecha", bindings.describe(Action::Confirm), bindings.describe(Action::Cancel)),
+        panel.x + 20.0,
+        panel.y + panel.h - 20.0,
+        22.0,
+        WHITE,
+    );
+}
+
+// Efeitos do jogador no canto de cima da tela: ícone e turnos que faltam
+fn draw_statuses(statuses: &Statuses, texture: &Texture2D) {
+    for (i, effect) in statuses.effects().iter().enumerate() {
+        let x = SCREEN_WIDTH - (i + 1) as f32 * (status::ICON_SIZE + 40.0);
+        draw_texture_ex(
+            texture,
+            x,
+            10.0,
+            WHITE,
+            DrawTextureParams {
+                source: Some(effect.kind.icon_source()),
+                ..Default::default()
+            },
+        );
+        draw_text(&effect.turns.to_string(), x + status::ICON_SIZE + 4.0, 32.0, 22.0, WHITE);
+    }
+}
+
+// Últimas LOG_LINES mensagens, a mais nova embaixo (coordenadas de tela)
+fn draw_log(log: &[String], color: Color) {
+    let start = log.len().saturating_sub(LOG_LINES);
+    for (i, message) in log[start..].iter().enumerate() {
+        let y = SCREEN_HEIGHT - 20.0 - (log.len() - start - 1 - i) as f32 * 26.0;
+        draw_text(message, 10.0, y, 22.0, color);
     }
 }
 
+// Área clicável da opção i do menu principal
+fn menu_item_rect(i: usize) -> Rect {
+    let (x, y) = MENU_POSITIONS[i];
+    Rect::new(x, y + MENU_ITEM_OFFSET_Y, MENU_ITEM_SIZE.0, MENU_ITEM_SIZE.1)
+}
+
+// Câmera do jogo, centrada onde o jogador está desenhado
+fn game_camera(player: &Player) -> Camera2D {
+    // O retângulo da câmera define a porção do "mundo" visível.
+    Camera2D::from_display_rect(Rect::new(
+        player.motion.x - SCREEN_WIDTH / 2.0,  // Canto esquerdo do mundo visível
+        player.motion.y + SCREEN_HEIGHT / 2.0, // Canto superior do mundo visível (invertido)
+        SCREEN_WIDTH,                          // Largura do mundo visível
+        -SCREEN_HEIGHT,                        // Altura do mundo visível (negativa para inverter Y) <-estranho mas ok, bom saber q nao colocar negativo poe de cabeça pra baixo.
+    ))
+}
+
+// Tile conhecido (explorado e andável) embaixo do mouse, passando pela câmera do jogo
+fn hovered_tile(run: &Run, registry: &TileRegistry) -> Option<(usize, usize)> {
+    let world = game_camera(&run.player).screen_to_world(mouse_position().into());
+    if world.x < 0.0 || world.y < 0.0 {
+        return None;
+    }
+    let (col, row) = ((world.x / TILE_SIZE) as usize, (world.y / TILE_SIZE) as usize);
+    let floor = run.dungeon.floor();
+    let known = floor.explored.get(col, row) && registry.is_walkable(floor.map.tile(col as isize, row as isize));
+    known.then_some((col, row))
+}
+
+// Centro (em pixels) do tile (col, row)
+fn tile_center(col: usize, row: usize) -> (f32, f32) {
+    (
+        (col as f32 * TILE_SIZE) + (TILE_SIZE / 2.0), // Centro X
+        (row as f32 * TILE_SIZE) + (TILE_SIZE / 2.0), // Centro Y
+    )
+}
+
+// Monta um jogo novo: cria a masmorra com a seed e o jogador num spawn do primeiro andar.
+async fn new_run(
+    seed: u64,
+    layouts: &LayoutTable,
+    registry: &TileRegistry,
+    assets: &mut GameAssets,
+) -> Result<Run, Box<dyn Error>> {
+    let dungeon = Dungeon::new(seed, layouts, registry).await?;
+    let map = &dungeon.floor().map;
+    assets.load_tileset(&map.tileset).await?;
+    println!("Mapa criado: {} ({}x{})", map.name, map.width, map.height);
+
+    // Tiles com a flag spawn servem de spawnpoint, apenas pra gerar algo meio aleatorio.
+    // Tanto o Map::parse quanto o gerador garantem que existe pelo menos um.
+    let spawn_points = map.spawn_tiles(registry);
+
+    // Salva (F5) no primeiro slot livre, ou por cima do save mais antigo. Só
+    // escolhe, quem confere se ainda está livre é o save::save_run.
+    let slot = save::pick_slot(&save::scan_slots(registry, layouts));
+    let mut run = Run::new(dungeon, Player::new(0, 0), slot);
+
+    // --- LÓGICA DE SPAWN DO JOGADOR ---
+    // Escolhe um ponto de spawn da lista com o Rng do jogo (mesma seed, mesmo lugar)
+    // e põe o jogador lá, parado
+    let (spawn_col, spawn_row) = spawn_points[run.rng.range(0, spawn_points.len())];
+    run.player.place(spawn_col, spawn_row);
+    println!("Jogador criado em: ({}, {})", spawn_col, spawn_row);
+    Ok(run)
+}
+
+// Carrega o jogo salvo no slot e deixa ele pronto pra continuar.
+async fn load_run(
+    slot: usize,
+    layouts: &LayoutTable,
+    registry: &TileRegistry,
+    assets: &mut GameAssets,
+) -> Result<Run, Box<dyn Error>> {
+    let run = save::load_slot(slot, registry, layouts)?.ok_or("slot vazio")?.run;
+    let mut run = prepare_run(run, registry, assets).await?;
+    run.log.push(format!("Jogo carregado do slot {}.", slot));
+    Ok(run)
+}
+
+// Mesma coisa pro autosave (opção Continuar do menu).
+async fn continue_run(layouts: &LayoutTable, registry: &TileRegistry, assets: &mut GameAssets) -> Result<Run, Box<dyn Error>> {
+    let run = save::load_autosave(registry, layouts)?.ok_or("sem autosave")?.run;
+    let mut run = prepare_run(run, registry, assets).await?;
+    run.log.push("Jogo continuado do autosave.".to_owned());
+    Ok(run)
+}
+
+// Jogo lido do disco: carrega o tileset do andar atual e recalcula a visão.
+async fn prepare_run(mut run: Run, registry: &TileRegistry, assets: &mut GameAssets) -> Result<Run, Box<dyn Error>> {
+    assets.load_tileset(&run.dungeon.floor().map.tileset).await?;
+    let origin = run.player.tile();
+    run.dungeon.update_view(origin, run.player.fov(), registry);
+    Ok(run)
+}
+
+// Ação de jogo -> comando do turno
+fn command_for(action: Action) -> Option<Command> {
+    match action {
+        Action::MoveRight => Some(Command::Move { dx: 1, dy: 0 }),
+        Action::MoveLeft => Some(Command::Move { dx: -1, dy: 0 }),
+        Action::MoveDown => Some(Command::Move { dx: 0, dy: 1 }),
+        Action::MoveUp => Some(Command::Move { dx: 0, dy: -1 }),
+        Action::MoveUpLeft => Some(Command::Move { dx: -1, dy: -1 }),
+        Action::MoveUpRight => Some(Command::Move { dx: 1, dy: -1 }),
+        Action::MoveDownLeft => Some(Command::Move { dx: -1, dy: 1 }),
+        Action::MoveDownRight => Some(Command::Move { dx: 1, dy: 1 }),
+        Action::Wait => Some(Command::Wait),
+        _ => None,
+    }
+}
+
+// Autosave não pode travar o jogo: se falhar só avisa.
+fn autosave(run: &mut Run) {
+    match save::autosave(run) {
+        Ok(()) => println!("Autosave feito."),
+        Err(e) => {
+            let message = format!("Falha no autosave: {}", e);
+            println!("{}", message);
+            run.log.push(message);
+        }
+    }
+}
+
+// Continuar (opção 0) só aparece se tem autosave. Olha o disco, então só roda
+// ao entrar no MainScreen (abertura e cada volta pro menu), não todo frame.
+fn first_menu_option() -> usize {
+    if save::has_autosave() { 0 } else { 1 }
+}
+
+// Desce (ou sobe) pra profundidade depth e põe o jogador na escada correspondente:
+// descendo chega no '<' do andar novo, subindo chega no '>' do andar de cima.
+async fn change_floor(
+    depth: u32,
+    run: &mut Run,
+    layouts: &LayoutTable,
+    registry: &TileRegistry,
+    assets: &mut GameAssets,
+) -> Result<(), Box<dyn Error>> {
+    let going_down = depth > run.dungeon.depth();
+    let floor = run.dungeon.go_to(depth, layouts, registry).await?;
+    assets.load_tileset(&floor.map.tileset).await?;
+
+    let arrival = if going_down { STAIRS_UP } else { STAIRS_DOWN };
+    let (col, row) = floor
+        .map
+        .find(arrival)
+        .expect("Dungeon garante escadas nos andares intermediários");
+    run.player.place(col, row);
+    println!("Andar {}: {} (jogador em {}, {})", floor.depth, floor.map.name, col, row);
+    run.cancel_route(); // Caminho era do andar de antes
+    Ok(())
+}
+
 // --- CONFIGURAÇÃO DE JANELA ---
 
 fn window_conf() -> Conf {
@@ -186,52 +615,104 @@ fn window_conf() -> Conf {
 async fn main() {
     set_pc_assets_folder("assets");
 
-    let assets = match GameAssets::load().await {
+    let tile_registry = match TileRegistry::load(TILE_TABLE).await {
+        Ok(r) => r,
+        Err(e) => {
+            panic!("Falha ao carregar a tabela de tiles '{}': {}", TILE_TABLE, e);
+        }
+    };
+    println!("Tabela de tiles carregada: {} tipos ({} bloqueiam visão)", tile_registry.len(), tile_registry.opaque_count());
+
+    let layouts = match LayoutTable::load(DUNGEON_CONFIG).await {
+        Ok(l) => l,
+        Err(e) => {
+            panic!("Falha ao carregar o layout da masmorra '{}': {}", DUNGEON_CONFIG, e);
+        }
+    };
+
+    // Controles do usuário. Arquivo quebrado não impede de jogar: fica com os padrões.
+    let mut bindings = match Bindings::load() {
+        Ok(b) => b,
+        Err(e) => {
+            println!("Falha ao ler os controles, usando os padrões: {}", e);
+            Bindings::default()
+        }
+    };
+    let mut gamepads = Gamepads::new(); // Controle alimenta as mesmas ações do teclado
+
+    let mut assets = match GameAssets::load().await {
         Ok(a) => a,
         Err(e) => {
-            panic!("Falha ao carregar assets. Verifique se os arquivos (splash01-05.png, main.png, dng/map1.png, arrow.png, BG/load.png, sprites/Player.png) estão na pasta 'assets'. Erro: {:?}", e);
+            panic!("Falha ao carregar assets. Verifique se os arquivos (splash01-05.png, main.png, arrow.png, BG/load.png, sprites/Player.png, sprites/monsters.png, sprites/items.png, sprites/equipment.png, sprites/status.png) estão na pasta 'assets'. Erro: {:?}", e);
         }
     };
 
+    // Tipos de monstro e item. Vem depois das texturas pra conferir os sprites nelas.
+    let sheets = content::Sheets {
+        monsters: assets.monster_texture.size(),
+        items: assets.item_texture.size(),
+        equipment: assets.equipment_texture.size(),
+    };
+    if let Err(e) = content::load(&sheets).await {
+        panic!("Falha ao carregar o conteúdo do jogo: {}", e);
+    }
+    println!("Conteúdo carregado: {} tipos de monstro, {} tipos de item", content::monsters().len(), content::items().len());
+
     let mut game_state = GameState::Splash;
     let mut current_splash_index: usize = 0;
     let mut state_timer: f32 = 0.0;
-    let mut menu_selection: i32 = 0; // 0: NewGame, 1: Load, 2: Exit
+    let mut menu_selection: usize = 1; // Índice em MENU_OPTIONS, começa no NewGame
+    let mut menu_first = first_menu_option(); // Primeira opção visível (0 se tiver autosave pra Continuar)
+    let mut last_mouse = mouse_position(); // Mouse só muda a seleção quando mexe
 
-    // --- Ingame Variables ---
-    let mut player: Option<Player> = None; // Jogador no momento opcional, pois só existe InGame
-
-    // Tiles marcados como 's' servem de spawnpoint, apenas pra gerar algo meio aleatorio.
-    let mut spawn_points: Vec<(f32, f32)> = Vec::new();
-    for (row_idx, row_str) in MAP_DATA.iter().enumerate() {
-        for (col_idx, tile_char) in row_str.chars().enumerate() {
-            if tile_char == 's' {
-                // Adiciona o *centro* do tile de spawn à lista... Uff...
-                spawn_points.push((
-                    (col_idx as f32 * TILE_SIZE) + (TILE_SIZE / 2.0), // Centro X
-                    (row_idx as f32 * TILE_SIZE) + (TILE_SIZE / 2.0), // Centro Y
-                ));
-            }
-        }
-    }
+    // --- Load Variables ---
+    // Slots lidos ao entrar na tela de Load
+    let mut load_slots: Vec<SlotStatus> = Vec::new();
+    let mut load_selection: usize = 0;
 
-    if spawn_points.is_empty() {
-        // Se não houver 's' no mapa, o jogo não pode começar.
-        panic!("O mapa (MAP_DATA) não contém nenhum ponto de spawn 's'.");
-    }
+    // --- Controls Variables ---
+    // Seleção vai até Action::ALL.len(), o último item é "Restaurar padrões"
+    let mut controls_selection: usize = 0;
+    let mut controls_waiting = false; // Esperando a tecla nova pra ação selecionada
+    let mut controls_message: Option<String> = None; // Aviso da última troca (tecla já usada)
+
+    // --- Inventory Variables ---
+    let mut inventory_selection: usize = 0; // Linha selecionada do inventário
+
+    // --- Character Variables ---
+    let mut perk_selection: usize = 0; // Índice em Perk::ALL
+
+    // --- Ingame Variables ---
+    // Jogador, masmorra e relógio ficam no Run, opcional pois só existe InGame
+    let mut run: Option<Run> = None;
+    let mut input_buffer = InputBuffer::default(); // Tecla segurada e aperto no meio da animação
 
     
+    // Fechar a janela passa pelo loop, pra dar tempo de fazer o autosave
+    prevent_quit();
+
     loop {
         let delta_time = get_frame_time(); 
+        bindings.set_pad(gamepads.poll(bindings.stick_deadzone));
+        let mouse = mouse_position();
+        let mouse_moved = mouse != last_mouse;
+        last_mouse = mouse;
+        let clicked = is_mouse_button_pressed(MouseButton::Left);
+
+        if is_quit_requested() {
+            if matches!(game_state, GameState::InGame | GameState::Controls | GameState::Inventory | GameState::Character)
+                && let Some(current_run) = run.as_mut()
+            {
+                autosave(current_run);
+            }
+            println!("Fechando o jogo.");
+            exit(0);
+        }
+
+        let skip_input = bindings.pressed(Action::Skip);
 
-        let skip_input = is_key_pressed(KeyCode::Enter)
-            || is_key_pressed(KeyCode::Z)
-            || is_key_pressed(KeyCode::X)
-            || is_key_pressed(KeyCode::C)
-            || is_key_pressed(KeyCode::D);
-            
         // Input de confirmação específico do menu
-        let confirm_input = is_key_pressed(KeyCode::Enter) || is_key_pressed(KeyCode::Z);
+        let confirm_input = bindings.pressed(Action::Confirm);
 
         // --- ATUALIZAÇÃO DE ESTADO ---
 
@@ -250,134 +731,314 @@ async fn main() {
                 }
             },
             GameState::MainScreen => { //-----------------------------------------------------------MAINSCREEN
+                menu_selection = menu_selection.max(menu_first);
+                let visible_count = MENU_OPTIONS.len() - menu_first;
+
                 // Sobe e desce basico do menu.
-                if is_key_pressed(KeyCode::Down) {
-                    menu_selection = (menu_selection + 1) % MENU_OPTIONS_COUNT;
+                if bindings.pressed(Action::MoveDown) {
+                    menu_selection = menu_first + (menu_selection - menu_first + 1) % visible_count;
                 }
-                if is_key_pressed(KeyCode::Up) {
-                    menu_selection = (menu_selection - 1 + MENU_OPTIONS_COUNT) % MENU_OPTIONS_COUNT;
+                if bindings.pressed(Action::MoveUp) {
+                    menu_selection = menu_first + (menu_selection - menu_first + visible_count - 1) % visible_count;
+                }
+
+                // Mouse: passar por cima seleciona, clicar confirma
+                let mouse_item = (menu_first..MENU_OPTIONS.len()).find(|&i| menu_item_rect(i).contains(mouse.into()));
+                if let Some(i) = mouse_item
+                    && (mouse_moved || clicked)
+                {
+                    menu_selection = i;
                 }
 
                 // Lógica de confirmação do menu. Eventualmente separa melhor essa parte.
-                if confirm_input {
-                    match menu_selection {
-                        0 => { // Novo Jogo
-                            game_state = GameState::InGame; // ---------------------------- Indo InGame
-                            println!("Entrando no estado InGame.");
-                            
-                            // --- LÓGICA DE SPAWN DO JOGADOR ---
-                            // Escolhe um ponto de spawn aleatório da lista
-                            let spawn_index = rand::gen_range(0, spawn_points.len());
-                            let (spawn_x, spawn_y) = spawn_points[spawn_index];
-                            
-                            // Cria o jogador nesse ponto, inicializando o movimento para a posição atual
-                            player = Some(Player { 
-                                x: spawn_x, 
-                                y: spawn_y, 
-                                start_x: spawn_x,
-                                start_y: spawn_y,
-                                target_x: spawn_x, 
-                                target_y: spawn_y, 
-                                move_timer: 0.0,   
-                            });
-                            println!("Jogador criado em: ({}, {})", spawn_x, spawn_y);
+                if confirm_input || (clicked && mouse_item.is_some()) {
+                    match MENU_OPTIONS[menu_selection] {
+                        MenuOption::Continue => {
+                            match continue_run(&layouts, &tile_registry, &mut assets).await {
+                                Ok(resumed) => {
+                                    run = Some(resumed);
+                                    game_state = GameState::InGame; // ---------------------------- Indo InGame
+                                    println!("Autosave carregado. Entrando no estado InGame.");
+                                },
+                                Err(e) => {
+                                    println!("Falha ao continuar: {}", e);
+                                }
+                            }
                         },
-                        1 => { // ------------------------------------------------------------ Indo LOAD
+                        MenuOption::NewGame => { // Novo Jogo
+                            // Seed nova a cada jogo (ms desde a epoch). Vai no nome do mapa gerado.
+                            let seed = (macroquad::miniquad::date::now() * 1000.0) as u64;
+                            match new_run(seed, &layouts, &tile_registry, &mut assets).await {
+                                Ok(new_run) => {
+                                    run = Some(new_run);
+                                    game_state = GameState::InGame; // ---------------------------- Indo InGame
+                                    println!("Entrando no estado InGame. Seed: {}", seed);
+                                },
+                                Err(e) => {
+                                    // Fica no menu, o jogo não pode começar sem mapa.
+                                    println!("Falha ao criar o mapa: {}", e);
+                                }
+                            }
+                        },
+                        MenuOption::Load => { // ------------------------------------------------ Indo LOAD
+                            load_slots = save::scan_slots(&tile_registry, &layouts);
+                            load_selection = 0;
                             game_state = GameState::Load;
                             println!("Entrando no estado Load.");
                         },
-                        2 => { // Sair
+                        MenuOption::Exit => { // Sair
                             println!("Fechando o jogo.");
                             exit(0);
                         },
-                        _ => {} // Nunca deve acontecer
                     }
                 }
             },
             GameState::Load => { //--------------------------------------------------------------LOAD
-                // Lógica da tela de Load
-                if is_key_pressed(KeyCode::X) {
+                // Escolhe o slot com ↑/↓, carrega com confirmação. Vazio ou quebrado não carrega.
+                if bindings.pressed(Action::MoveDown) {
+                    load_selection = (load_selection + 1) % save::SLOT_COUNT;
+                }
+                if bindings.pressed(Action::MoveUp) {
+                    load_selection = (load_selection + save::SLOT_COUNT - 1) % save::SLOT_COUNT;
+                }
+
+                if confirm_input && matches!(load_slots[load_selection], SlotStatus::Ready { .. }) {
+                    let slot = load_selection + 1;
+                    match load_run(slot, &layouts, &tile_registry, &mut assets).await {
+                        Ok(loaded) => {
+                            run = Some(loaded);
+                            game_state = GameState::InGame; // ---------------------------- Indo InGame
+                            println!("Slot {} carregado. Entrando no estado InGame.", slot);
+                        },
+                        Err(e) => {
+                            // Mudou entre a listagem e agora: mostra o erro no lugar do slot
+                            println!("Falha ao carregar o slot {}: {}", slot, e);
+                            load_slots[load_selection] = SlotStatus::Broken(e.to_string());
+                        }
+                    }
+                }
+
+                if bindings.pressed(Action::Cancel) {
+                    menu_first = first_menu_option();
                     game_state = GameState::MainScreen;
                     println!("Voltando para MainScreen.");
                 }
             },
+            GameState::GameOver => { //----------------------------------------------------------GAMEOVER
+                // O Run fica vivo só pra tela mostrar como foi, sai pro menu com confirmação
+                if confirm_input {
+                    menu_first = first_menu_option();
+                    game_state = GameState::MainScreen;
+                    run = None;
+                    println!("Voltando para MainScreen.");
+                }
+            },
             GameState::InGame => { //------------------------------------------------------------INGAME
-                let p = player.as_mut().expect("Player deve existir em InGame");
-                
-                // 1. Lógica de movimento/animação
-                if p.move_timer > 0.0 {
-                    // Está se movendo, diminui o timer
-                    p.move_timer -= delta_time;
-                    p.move_timer = p.move_timer.max(0.0);
-                    
-                    if p.move_timer <= 0.0 {
-                        // Movimento finalizado. Trava a posição no alvo.
-                        p.x = p.target_x;
-                        p.y = p.target_y;
-                    } else {
-                        // Interpolação Linear (LERP)
-                        let t = 1.0 - (p.move_timer / MOVE_DURATION); // Progresso de 0.0 a 1.0
-                        
-                        // Interpola a posição (x, y) entre start e target
-                        p.x = p.start_x + (p.target_x - p.start_x) * t;
-                        p.y = p.start_y + (p.target_y - p.start_y) * t;
-                    }
-                } else {
-                    // 2. Não está se movendo. Checa por novo input.
-                    
-                    let mut target_col_offset: f32 = 0.0;
-                    let mut target_row_offset: f32 = 0.0;
-                    let mut moved = false;
-                    
-                    if is_key_pressed(KeyCode::Right) {
-                        target_col_offset = 1.0;
-                        moved = true;
-                    } else if is_key_pressed(KeyCode::Left) {
-                        target_col_offset = -1.0;
-                        moved = true;
-                    } else if is_key_pressed(KeyCode::Down) {
-                        target_row_offset = 1.0;
-                        moved = true;
-                    } else if is_key_pressed(KeyCode::Up) {
-                        target_row_offset = -1.0;
-                        moved = true;
+                let current_run = run.as_mut().expect("Run deve existir em InGame");
+
+                // 1. Animação do passo. Só apresentação, a posição já mudou quando o turno resolveu.
+                let step_finished = current_run.player.motion.update(delta_time);
+                for monster in current_run.dungeon.floor_mut().monsters.iter_mut() {
+                    monster.motion.update(delta_time);
+                }
+
+                // Terminou o passo em cima de uma escada? Troca de andar (e faz autosave).
+                if step_finished && let Some(depth) = current_run.stairs_destination(&tile_registry) {
+                    match change_floor(depth, current_run, &layouts, &tile_registry, &mut assets).await {
+                        Ok(()) => autosave(current_run),
+                        // Fica no andar atual, o jogador pode tentar de novo
+                        Err(e) => println!("Falha ao trocar de andar: {}", e),
                     }
-                    
-                    if moved {
-                        // Calcula a posição do centro do tile alvo (p.x/y atual é a posição do tile)
-                        let new_x = p.x + target_col_offset * TILE_SIZE;
-                        let new_y = p.y + target_row_offset * TILE_SIZE;
-                        
-                        // Checa colisão com o novo tile (apenas o centro do tile importa)
-                        let tile_char = get_tile_char(new_x, new_y);
-                        
-                        // 'x' é muro. Espaço (' ') é fora do mapa. 'o' e 's' são chão.
-                        if tile_char != 'x' && tile_char != ' ' {
-                            // Movimento válido, inicia a animação
-                            p.start_x = p.x; // Posição de partida é a atual (centro do tile)
-                            p.start_y = p.y; // Posição de partida é a atual (centro do tile)
-                            p.target_x = new_x; // Novo alvo (centro do próximo tile)
-                            p.target_y = new_y; // Novo alvo (centro do próximo tile)
-                            p.move_timer = MOVE_DURATION;
-                        }
+                }
+
+                // 2. Não está se movendo. Checa por novo input e resolve o turno.
+                //    Aperto durante a animação fica no buffer; tecla segurada repete.
+                //    Clique num tile conhecido viaja até lá, um passo por vez; tecla cancela a viagem.
+                //    Auto-explorar é a mesma viagem, com o destino refeito a cada passo.
+                let ready = !current_run.player.motion.is_moving();
+                if clicked && let Some(target) = hovered_tile(current_run, &tile_registry) {
+                    current_run.travel_to(target, &tile_registry);
+                }
+                if bindings.pressed(Action::Explore) {
+                    current_run.explore();
+                }
+                if ready && bindings.pressed(Action::Pickup) {
+                    current_run.cancel_route();
+                    current_run.perform(Command::Pickup, &tile_registry);
+                }
+                if let Some(command) = input_buffer.update(&bindings, &GAME_ACTIONS, delta_time, ready).and_then(command_for) {
+                    current_run.cancel_route();
+                    let command = current_run.confuse(command, &tile_registry); // Confuso às vezes erra a direção
+                    current_run.perform(command, &tile_registry);
+                } else if ready && current_run.is_traveling() {
+                    current_run.follow_route(&tile_registry);
+                }
+
+                // Morreu no turno dos monstros: fim de jogo. Sem autosave pra voltar atrás.
+                if current_run.is_over() {
+                    input_buffer = InputBuffer::default();
+                    game_state = GameState::GameOver;
+                    if let Err(e) = save::delete_autosave() {
+                        println!("Falha ao apagar o autosave: {}", e);
                     }
+                    println!("Jogador morreu. Entrando em GameOver.");
+                }
+
+                // 3. Campo de visão a partir do tile atual (já no andar novo, se trocou)
+                let origin = current_run.player.tile();
+                current_run.dungeon.update_view(origin, current_run.player.fov(), &tile_registry);
+
+                // Salva no slot do jogo. Se morreu nesse frame não salva, senão
+                // o slot ficaria com um jogo já perdido.
+                if bindings.pressed(Action::Save) && !current_run.is_over() {
+                    let message = match save::save_run(current_run, &tile_registry, &layouts) {
+                        Ok(slot) => format!("Jogo salvo no slot {}.", slot),
+                        Err(e) => format!("Falha ao salvar: {}", e),
+                    };
+                    println!("{}", message);
+                    current_run.log.push(message);
+                }
+
+                if bindings.pressed(Action::Inventory) && game_state == GameState::InGame {
+                    current_run.cancel_route();
+                    // Começa no primeiro item da mochila, ou nos slots se ela estiver vazia
+                    inventory_selection = if current_run.player.inventory.stacks().is_empty() { 0 } else { EquipSlot::ALL.len() };
+                    game_state = GameState::Inventory;
                 }
 
-                // Lógica para sair do jogo
-                 if is_key_pressed(KeyCode::Escape) {
+                if bindings.pressed(Action::Character) && game_state == GameState::InGame {
+                    current_run.cancel_route();
+                    perk_selection = 0;
+                    game_state = GameState::Character;
+                }
+
+                // Lógica para sair do jogo (com autosave, dá pra Continuar depois)
+                 if bindings.pressed(Action::QuitToMenu) && game_state == GameState::InGame {
+                    autosave(current_run);
+                    input_buffer = InputBuffer::default();
+                    menu_first = first_menu_option();
                     game_state = GameState::MainScreen;
-                    player = None; // Remove o jogador ao sair para o menu
+                    run = None; // Remove o jogador (e a masmorra) ao sair para o menu
                     println!("Voltando para MainScreen.");
                 }
+
+                if bindings.pressed(Action::Controls) && game_state == GameState::InGame {
+                    controls_selection = 0;
+                    controls_waiting = false;
+                    controls_message = None;
+                    game_state = GameState::Controls;
+                    println!("Entrando no estado Controls.");
+                }
+            },
+            GameState::Inventory => { //---------------------------------------------------------INVENTORY
+                // Jogo pausado por baixo. Usar, equipar, tirar ou largar gasta o turno e volta
+                // pro jogo, pra ver os monstros reagirem. Item que não se usa só avisa e fica aqui.
+                // Linhas: os slots de equipamento primeiro, depois a mochila.
+                let current_run = run.as_mut().expect("Run deve existir em Inventory");
+                let slots = EquipSlot::ALL.len();
+                let count = slots + current_run.player.inventory.stacks().len();
+                if bindings.pressed(Action::MoveDown) {
+                    inventory_selection = (inventory_selection + 1) % count;
+                }
+                if bindings.pressed(Action::MoveUp) {
+                    inventory_selection = (inventory_selection + count - 1) % count;
+                }
+
+                let command = match inventory_selection.checked_sub(slots) {
+                    None if confirm_input => Some(Command::Unequip { slot: EquipSlot::ALL[inventory_selection] }),
+                    Some(index) if confirm_input => {
+                        let stack = current_run.player.inventory.stacks()[index];
+                        if stack.kind.equip.is_some() {
+                            Some(Command::Equip { index })
+                        } else {
+                            Some(Command::Use { index })
+                        }
+                    }
+                    Some(index) if bindings.pressed(Action::Drop) => Some(Command::Drop { index }),
+                    _ => None,
+                };
+                let acted = command.is_some_and(|command| current_run.perform(command, &tile_registry));
+
+                if acted || bindings.pressed(Action::Cancel) || bindings.pressed(Action::Inventory) {
+                    input_buffer = InputBuffer::default();
+                    game_state = GameState::InGame;
+                }
+            },
+            GameState::Character => { //---------------------------------------------------------CHARACTER
+                // Jogo pausado por baixo. Escolher vantagem não gasta turno, então a tela
+                // continua aberta pra ver os atributos novos (e gastar outro ponto, se tiver).
+                let current_run = run.as_mut().expect("Run deve existir em Character");
+                if bindings.pressed(Action::MoveDown) {
+                    perk_selection = (perk_selection + 1) % Perk::ALL.len();
+                }
+                if bindings.pressed(Action::MoveUp) {
+                    perk_selection = (perk_selection + Perk::ALL.len() - 1) % Perk::ALL.len();
+                }
+                if confirm_input {
+                    current_run.choose_perk(Perk::ALL[perk_selection]);
+                }
+                if bindings.pressed(Action::Cancel) || bindings.pressed(Action::Character) {
+                    input_buffer = InputBuffer::default();
+                    game_state = GameState::InGame;
+                }
+            },
+            GameState::Controls => { //----------------------------------------------------------CONTROLS
+                // Jogo pausado por baixo. Confirm numa ação espera a próxima tecla e troca.
+                if controls_waiting {
+                    // Cancel desiste da troca. Tecla que não dá pra salvar no input.cfg
+                    // é ignorada, continua esperando.
+                    if bindings.pressed(Action::Cancel) {
+                        controls_waiting = false;
+                    } else if let Some(key) = get_last_key_pressed()
+                        && input::is_bindable(key)
+                    {
+                        let action = Action::ALL[controls_selection];
+                        controls_waiting = false;
+                        match bindings.rebind(action, key) {
+                            Ok(()) => {
+                                println!("{} agora é {}", action.name(), input::key_name(key));
+                                if let Err(e) = bindings.save() {
+                                    println!("Falha ao salvar os controles: {}", e);
+                                }
+                            }
+                            Err(other) => {
+                                controls_message = Some(format!("{} já é usada em \"{}\"", input::key_name(key), other.label()));
+                            }
+                        }
+                    }
+                } else {
+                    let item_count = Action::ALL.len() + 1;
+                    if bindings.pressed(Action::MoveDown) {
+                        controls_selection = (controls_selection + 1) % item_count;
+                    }
+                    if bindings.pressed(Action::MoveUp) {
+                        controls_selection = (controls_selection + item_count - 1) % item_count;
+                    }
+
+                    if confirm_input {
+                        controls_message = None;
+                        if controls_selection < Action::ALL.len() {
+                            controls_waiting = true;
+                        } else {
+                            // Restaurar padrões
+                            bindings.reset_keys();
+                            if let Err(e) = bindings.save() {
+                                println!("Falha ao salvar os controles: {}", e);
+                            }
+                        }
+                    } else if bindings.pressed(Action::Cancel) {
+                        input_buffer = InputBuffer::default(); // Nada apertado no menu vaza pro jogo
+                        game_state = GameState::InGame;
+                        println!("Voltando para InGame.");
+                    }
+                }
             }
         }
 
         // --- DESENHO (DRAW) ---
         
         clear_background(BACKGROUND_COLOR);
-        
-        // Variável para armazenar as coordenadas do jogador para debug na UI
-        let mut player_coords = String::new(); 
+
 
         match game_state {
             GameState::Splash => {
@@ -419,9 +1080,14 @@ async fn main() {
                     }
                 );
                 
+                // Continuar não está no main.png, vai como texto em cima do "New Game"
+                if menu_first == 0 {
+                    draw_text("Continuar", 690.0, 455.0, 72.0, Color::new(0.96, 0.96, 0.96, 1.0));
+                }
+
                 // --- Desenha o Seletor ---
                 // Pega a posição X,Y do array com base na seleção atual
-                let (selector_x, selector_y) = MENU_POSITIONS[menu_selection as usize];
+                let (selector_x, selector_y) = MENU_POSITIONS[menu_selection];
                 
                 draw_texture(&assets.selector_texture, selector_x, selector_y, WHITE);
             },
@@ -439,66 +1105,216 @@ async fn main() {
                 );
                 
                 draw_text("TELA DE LOAD", 50.0, 50.0, 30.0, YELLOW);
-                draw_text("Pressione X para voltar", 50.0, 90.0, 24.0, WHITE);
+                draw_text(
+                    &format!("{} carrega o slot, {} volta", bindings.describe(Action::Confirm), bindings.describe(Action::Cancel)),
+                    50.0,
+                    90.0,
+                    24.0,
+                    WHITE,
+                );
+
+                // Um slot por linha, abaixo do título do fundo
+                for (i, status) in load_slots.iter().enumerate() {
+                    let y = 450.0 + i as f32 * 80.0;
+                    let (line, detail, color) = match status {
+                        SlotStatus::Empty => (format!("Slot {} - vazio", i + 1), String::new(), LIGHTGRAY),
+                        SlotStatus::Ready { saved_at, depth, turn, map_name } => (
+                            format!("Slot {} - Andar {} - Turno {}", i + 1, depth, turn),
+                            format!("{} | {}", map_name, save::format_timestamp(*saved_at)),
+                            WHITE,
+                        ),
+                        SlotStatus::Broken(reason) => (format!("Slot {} - save com erro", i + 1), reason.clone(), RED),
+                    };
+                    let color = if i == load_selection { YELLOW } else { color };
+                    if i == load_selection {
+                        draw_texture(&assets.selector_texture, 150.0, y - 40.0, WHITE);
+                    }
+                    draw_text(&line, 250.0, y, 32.0, color);
+                    draw_text(&detail, 250.0, y + 26.0, 20.0, color);
+                }
             },
-            GameState::InGame => {
+            GameState::InGame | GameState::Inventory | GameState::Character => {
+                let current_run = run.as_ref().expect("Run deve existir em InGame");
+                let current_dungeon = &current_run.dungeon;
+                let current_floor = current_dungeon.floor();
+                let current_map = &current_floor.map;
+                let p = &current_run.player;
+
                 // --- CÂMERA SEGUINDO O JOGADOR ---
-                if let Some(p) = &player {
-                    // Preenche a variável de coordenadas para debug na UI
-                    player_coords = format!("Posição: X={:.1}, Y={:.1}", p.x, p.y);
-                    
-                    // Define o ponto central da câmera (onde o jogador está)
-                    let center_x = p.x;
-                    let center_y = p.y;
-
-                    // O retângulo da câmera define a porção do "mundo" visível.
-                    let camera = Camera2D::from_display_rect(Rect::new(
-                        center_x - SCREEN_WIDTH / 2.0,  // Canto esquerdo do mundo visível
-                        center_y + SCREEN_HEIGHT / 2.0, // Canto superior do mundo visível (invertido)
-                        SCREEN_WIDTH,                   // Largura do mundo visível
-                        -SCREEN_HEIGHT,                 // Altura do mundo visível (negativa para inverter Y) <-estranho mas ok, bom saber q nao colocar negativo poe de cabeça pra baixo.
-                    ));
-                    set_camera(&camera);
-                } else {
-                    // Fallback
-                    let map_pixel_width = MAP_WIDTH as f32 * TILE_SIZE;
-                    let map_pixel_height = MAP_HEIGHT as f32 * TILE_SIZE;
-                    let camera = Camera2D::from_display_rect(Rect::new(
-                        -(SCREEN_WIDTH - map_pixel_width) / 2.0, 
-                        -(SCREEN_HEIGHT - map_pixel_height) / 2.0, 
-                        SCREEN_WIDTH, 
-                        SCREEN_HEIGHT
-                    ));
-                    set_camera(&camera);
-                }
+                let gear = p.effective_stats(); // Ataque e defesa com equipamento
+                // Coordenadas do jogador para debug na UI
+                let player_coords = format!(
+                    "Tile: ({}, {}) | Turno: {} | Nv {} XP {}/{} | HP: {}/{} | Atq {} Def {} | Dano: {}",
+                    p.col,
+                    p.row,
+                    current_run.scheduler.turn(),
+                    p.experience.level,
+                    p.experience.xp,
+                    p.experience.next(),
+                    p.stats.hp,
+                    p.stats.max_hp,
+                    gear.attack,
+                    gear.defense,
+                    p.stats.damage
+                );
+
+                // Câmera centrada onde o jogador está desenhado
+                set_camera(&game_camera(p));
 
                 // --- TESTE DE DEBUG: QUADRADO AZUL NO (0,0) ---
                 draw_rectangle(0.0, 0.0, TILE_SIZE, TILE_SIZE, BLUE);
 
 
                 // Chama a nossa nova função de desenho (agora sob a câmera)
-                draw_map(&assets.tileset_texture);
+                draw_map(current_floor, &tile_registry, assets.tileset(&current_map.tileset));
+                draw_items(current_floor, &assets.item_texture);
+                draw_monsters(current_floor, &assets.monster_texture);
+
+                // Tile conhecido embaixo do mouse: dá pra clicar e viajar até ele
+                if game_state == GameState::InGame
+                    && let Some((col, row)) = hovered_tile(current_run, &tile_registry)
+                {
+                    draw_rectangle_lines(col as f32 * TILE_SIZE, row as f32 * TILE_SIZE, TILE_SIZE, TILE_SIZE, 3.0, YELLOW);
+                }
 
                 // --- DESENHA O JOGADOR ---
-                if let Some(p) = &player {
-                    
-                    // Desenha o sprite do jogador
-                    let texture_width = assets.player_texture.width();
-                    let texture_height = assets.player_texture.height();
-                    
-                    let draw_x = p.x - (texture_width / 2.0);
-                    let draw_y = p.y - (texture_height / 2.0);
+                // Desenha o sprite do jogador
+                let texture_width = assets.player_texture.width();
+                let texture_height = assets.player_texture.height();
+
+                let draw_x = p.motion.x - (texture_width / 2.0);
+                let draw_y = p.motion.y - (texture_height / 2.0);
 
-                    draw_texture(&assets.player_texture, draw_x, draw_y, WHITE);
+                draw_texture(&assets.player_texture, draw_x, draw_y, WHITE);
+                // Equipamento por cima, na ordem dos slots (amuleto fica em cima da armadura)
+                for stack in p.equipment.worn() {
+                    if let Some(layer) = stack.kind.equip.as_ref().and_then(|equip| equip.layer) {
+                        draw_texture_ex(
+                            &assets.equipment_texture,
+                            draw_x,
+                            draw_y,
+                            WHITE,
+                            DrawTextureParams {
+                                source: Some(equipment::layer_source(layer)),
+                                ..Default::default()
+                            },
+                        );
+                    }
                 }
 
                 
                 // Reseta a câmera para desenhar o texto de UI
                 set_default_camera();
-                draw_text("MODO DE JOGO", 10.0, 30.0, 24.0, YELLOW);
-                draw_text("Pressione ESC para voltar ao menu.", 10.0, 60.0, 24.0, WHITE);
+                draw_text(&format!("MODO DE JOGO - Andar {}/{} - {}", current_dungeon.depth(), current_dungeon.max_depth(), current_map.name), 10.0, 30.0, 24.0, YELLOW);
+                draw_text(
+                    &format!(
+                        "{} volta ao menu. {} espera um turno. Ande contra um monstro para atacar. {} salva. {} controles.",
+                        bindings.describe(Action::QuitToMenu),
+                        bindings.describe(Action::Wait),
+                        bindings.describe(Action::Save),
+                        bindings.describe(Action::Controls),
+                    ),
+                    10.0,
+                    60.0,
+                    24.0,
+                    WHITE,
+                );
+                draw_text(
+                    &format!(
+                        "{} pega item. {} abre o inventário (peso {}/{}). {} personagem.",
+                        bindings.describe(Action::Pickup),
+                        bindings.describe(Action::Inventory),
+                        p.inventory.weight(),
+                        p.inventory.capacity,
+                        bindings.describe(Action::Character),
+                    ),
+                    10.0,
+                    90.0,
+                    24.0,
+                    WHITE,
+                );
+                if p.experience.perk_points > 0 {
+                    draw_text(
+                        &format!("Vantagem pra escolher! ({})", bindings.describe(Action::Character)),
+                        SCREEN_WIDTH - 300.0,
+                        90.0,
+                        24.0,
+                        YELLOW,
+                    );
+                }
                 // Desenha as coordenadas do jogador na UI (fora da câmera do jogo)
-                draw_text(&player_coords, 10.0, 90.0, 24.0, LIME);
+                draw_text(&player_coords, 10.0, 120.0, 24.0, LIME);
+
+                // Monstros à vista, com a vida de cada um
+                let in_view: Vec<String> = current_floor
+                    .monsters
+                    .iter()
+                    .filter(|m| current_floor.visible.get(m.col, m.row))
+                    .map(|m| {
+                        let effects: Vec<&str> = m.status.effects().iter().map(|e| e.kind.label()).collect();
+                        if effects.is_empty() {
+                            format!("{} {}/{}", m.kind.name, m.stats.hp, m.stats.max_hp)
+                        } else {
+                            format!("{} {}/{} ({})", m.kind.name, m.stats.hp, m.stats.max_hp, effects.join(", "))
+                        }
+                    })
+                    .collect();
+                if !in_view.is_empty() {
+                    draw_text(&format!("À vista: {}", in_view.join(", ")), 10.0, 150.0, 24.0, ORANGE);
+                }
+
+                draw_statuses(&p.status, &assets.status_texture);
+
+                // Últimas mensagens de combate, no pé da tela
+                draw_log(&current_run.log, WHITE);
+
+                if game_state == GameState::Inventory {
+                    draw_inventory(p, inventory_selection, &bindings);
+                }
+                if game_state == GameState::Character {
+                    draw_character(p, perk_selection, &bindings);
+                }
+            },
+            GameState::GameOver => {
+                let current_run = run.as_ref().expect("Run deve existir em GameOver");
+                draw_text("VOCÊ MORREU", 50.0, 120.0, 60.0, RED);
+                draw_text(
+                    &format!("Andar {} | Turno {}", current_run.dungeon.depth(), current_run.scheduler.turn()),
+                    50.0,
+                    170.0,
+                    30.0,
+                    WHITE,
+                );
+                draw_text(&format!("Pressione {} para voltar ao menu", bindings.describe(Action::Confirm)), 50.0, 220.0, 24.0, YELLOW);
+                draw_log(&current_run.log, LIGHTGRAY);
+            },
+            GameState::Controls => {
+                draw_text("CONTROLES", 50.0, 60.0, 40.0, YELLOW);
+                let help = if controls_waiting {
+                    format!("Pressione a nova tecla ({} desiste)", bindings.describe(Action::Cancel))
+                } else if let Some(message) = &controls_message {
+                    message.clone()
+                } else {
+                    format!(
+                        "{} troca a tecla, {} volta ao jogo",
+                        bindings.describe(Action::Confirm),
+                        bindings.describe(Action::Cancel)
+                    )
+                };
+                draw_text(&help, 50.0, 100.0, 24.0, WHITE);
+
+                // Uma ação por linha, com as teclas atuais. No fim, restaurar padrões.
+                for (i, action) in Action::ALL.iter().enumerate() {
+                    let y = 140.0 + i as f32 * 30.0;
+                    let selected = i == controls_selection;
+                    let color = if selected { YELLOW } else { WHITE };
+                    let keys = if selected && controls_waiting { "...".to_owned() } else { bindings.describe(*action) };
+                    draw_text(action.label(), 80.0, y, 26.0, color);
+                    draw_text(&keys, 480.0, y, 26.0, color);
+                }
+                let reset_y = 140.0 + Action::ALL.len() as f32 * 30.0 + 20.0;
+                let reset_color = if controls_selection == Action::ALL.len() { YELLOW } else { LIGHTGRAY };
+                draw_text("Restaurar padrões", 80.0, reset_y, 28.0, reset_color);
             }
         }
 
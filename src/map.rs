@@ -0,0 +1,298 @@
+use macroquad::prelude::*;
+use std::fmt;
+
+use crate::tiles::{TileFlag, TileRegistry, VOID_TILE};
+
+// --- MAPA ---
+// Formato dos arquivos de mapa (assets/maps/*.map):
+//
+//   # comentário
+//   name: Sala inicial
+//   tileset: dng/map1.png
+//   width: 16
+//   height: 5
+//   ---
+//   xxxxx     xxxxxx
+//   xsooooooooooooox
+//   ...
+//
+// Tudo antes do '---' é metadado (chave: valor), tudo depois é a grade do mapa.
+// A legenda dos glifos vem do TileRegistry (assets/tiles.cfg), ' ' = vazio.
+const GRID_SEPARATOR: &str = "---";
+
+// --- Erros ---
+#[derive(Debug)]
+pub enum MapError {
+    Io { path: String, source: macroquad::Error }, // Arquivo não encontrado/ilegível
+    MissingField(&'static str),                     // Metadado obrigatório ausente
+    InvalidField { field: String, value: String },  // Metadado com valor inválido
+    MissingGrid,                                    // Não achou o '---'
+    WrongHeight { expected: usize, found: usize },  // Número de linhas != height
+    RaggedRow { row: usize, expected: usize, found: usize }, // Linha com largura errada
+    UnknownTile { row: usize, col: usize, glyph: char },     // Caractere fora da legenda
+    NoSpawn,                                        // Nenhum tile com flag spawn
+    NoStairsDown,                                   // Andar do meio sem escada '>'
+    NoLayout(u32),                                  // Profundidade sem layout no dungeon.cfg
+}
+
+impl fmt::Display for MapError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MapError::Io { path, source } => write!(f, "não foi possível ler '{}': {}", path, source),
+            MapError::MissingField(field) => write!(f, "metadado obrigatório '{}' ausente", field),
+            MapError::InvalidField { field, value } => write!(f, "valor inválido para '{}': '{}'", field, value),
+            MapError::MissingGrid => write!(f, "separador '{}' da grade não encontrado", GRID_SEPARATOR),
+            MapError::WrongHeight { expected, found } => write!(f, "esperava {} linhas na grade, encontrou {}", expected, found),
+            MapError::RaggedRow { row, expected, found } => write!(f, "linha {} tem {} colunas, esperava {}", row, found, expected),
+            MapError::UnknownTile { row, col, glyph } => write!(f, "tile desconhecido '{}' na linha {}, coluna {}", glyph, row, col),
+            MapError::NoSpawn => write!(f, "o mapa não contém nenhum tile de spawn"),
+            MapError::NoStairsDown => write!(f, "o mapa não contém escada para descer"),
+            MapError::NoLayout(depth) => write!(f, "nenhum layout configurado para a profundidade {}", depth),
+        }
+    }
+}
+
+impl std::error::Error for MapError {}
+
+// --- Map ---
+pub struct Map {
+    pub name: String,    // Nome pra exibir
+    pub tileset: String, // Caminho do tileset dentro de assets/
+    pub width: usize,
+    pub height: usize,
+    tiles: Vec<char>, // Linha por linha, width * height
+}
+
+impl Map {
+    // Carrega e valida um mapa a partir de um arquivo dentro de assets/.
+    pub async fn load(path: &str, registry: &TileRegistry) -> Result<Self, MapError> {
+        let text = load_string(path).await.map_err(|source| MapError::Io {
+            path: path.to_owned(),
+            source,
+        })?;
+        Self::parse(&text, registry)
+    }
+
+    // Faz o parse do texto do mapa. Separado do load pra não depender de arquivo.
+    pub fn parse(text: &str, registry: &TileRegistry) -> Result<Self, MapError> {
+        let mut lines = text.lines();
+
+        // 1. Metadados até o separador
+        let mut name = None;
+        let mut tileset = None;
+        let mut width = None;
+        let mut height = None;
+        let mut found_separator = false;
+
+        for line in lines.by_ref() {
+            let line = line.trim();
+            if line == GRID_SEPARATOR {
+                found_separator = true;
+                break;
+            }
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let Some((key, value)) = line.split_once(':') else {
+                return Err(MapError::InvalidField { field: line.to_owned(), value: String::new() });
+            };
+            let (key, value) = (key.trim(), value.trim());
+            match key {
+                "name" => name = Some(value.to_owned()),
+                "tileset" => tileset = Some(value.to_owned()),
+                "width" => width = Some(parse_dimension(key, value)?),
+                "height" => height = Some(parse_dimension(key, value)?),
+                _ => return Err(MapError::InvalidField { field: key.to_owned(), value: value.to_owned() }),
+            }
+        }
+
+        if !found_separator {
+            return Err(MapError::MissingGrid);
+        }
+        let name = name.ok_or(MapError::MissingField("name"))?;
+        let tileset = tileset.ok_or(MapError::MissingField("tileset"))?;
+        let width = width.ok_or(MapError::MissingField("width"))?;
+        let height = height.ok_or(MapError::MissingField("height"))?;
+
+        // 2. Grade. Não usamos trim aqui, espaço é tile válido (vazio).
+        let rows: Vec<&str> = lines.collect();
+        if rows.len() != height {
+            return Err(MapError::WrongHeight { expected: height, found: rows.len() });
+        }
+
+        let mut tiles = Vec::with_capacity(width * height);
+        for (row_idx, row_str) in rows.iter().enumerate() {
+            let row_chars: Vec<char> = row_str.chars().collect();
+            if row_chars.len() != width {
+                return Err(MapError::RaggedRow { row: row_idx, expected: width, found: row_chars.len() });
+            }
+            for (col_idx, glyph) in row_chars.into_iter().enumerate() {
+                if !registry.contains(glyph) {
+                    return Err(MapError::UnknownTile { row: row_idx, col: col_idx, glyph });
+                }
+                tiles.push(glyph);
+            }
+        }
+
+        let map = Map { name, tileset, width, height, tiles };
+        if map.spawn_tiles(registry).is_empty() {
+            return Err(MapError::NoSpawn);
+        }
+        Ok(map)
+    }
+
+    // Monta um mapa a partir de uma grade já pronta (usado pelo gerador).
+    // Não valida nada, quem gera é responsável por usar glifos conhecidos.
+    pub fn from_grid(name: String, tileset: String, width: usize, height: usize, tiles: Vec<char>) -> Self {
+        assert_eq!(tiles.len(), width * height, "grade com tamanho errado");
+        Map { name, tileset, width, height, tiles }
+    }
+
+    // Caractere do tile em (col, row). Retorna ' ' se estiver fora dos limites.
+    pub fn tile(&self, col: isize, row: isize) -> char {
+        if row >= 0 && row < self.height as isize && col >= 0 && col < self.width as isize {
+            self.tiles[row as usize * self.width + col as usize]
+        } else {
+            VOID_TILE // Fora do limite
+        }
+    }
+
+    // Posição (col, row) da primeira ocorrência do glifo.
+    pub fn find(&self, glyph: char) -> Option<(usize, usize)> {
+        self.tiles().find(|&(_, _, g)| g == glyph).map(|(col, row, _)| (col, row))
+    }
+
+    // Troca o glifo de um tile. Fora dos limites não faz nada.
+    pub fn set(&mut self, col: usize, row: usize, glyph: char) {
+        if col < self.width && row < self.height {
+            self.tiles[row * self.width + col] = glyph;
+        }
+    }
+
+    // Troca todas as ocorrências de um glifo por outro.
+    pub fn replace(&mut self, from: char, to: char) {
+        for glyph in self.tiles.iter_mut().filter(|g| **g == from) {
+            *glyph = to;
+        }
+    }
+
+    // Iterador de (col, row, glyph) pra desenhar/varrer o mapa inteiro.
+    pub fn tiles(&self) -> impl Iterator<Item = (usize, usize, char)> + '_ {
+        self.tiles
+            .iter()
+            .enumerate()
+            .map(|(i, &glyph)| (i % self.width, i / self.width, glyph))
+    }
+
+    // Flood fill (4 direções) a partir de start, andando só por glifos passable.
+    // Retorna um vetor width * height marcando os tiles alcançados.
+    pub fn flood_fill(&self, start: (usize, usize), passable: impl Fn(char) -> bool) -> Vec<bool> {
+        flood_fill(self.width, self.height, &self.tiles, start, passable)
+    }
+
+    // Posições (col, row) de todos os tiles com a flag spawn.
+    pub fn spawn_tiles(&self, registry: &TileRegistry) -> Vec<(usize, usize)> {
+        self.tiles()
+            .filter(|&(_, _, glyph)| registry.has_flag(glyph, TileFlag::Spawn))
+            .map(|(col, row, _)| (col, row))
+            .collect()
+    }
+}
+
+fn parse_dimension(field: &str, value: &str) -> Result<usize, MapError> {
+    match value.parse::<usize>() {
+        Ok(n) if n > 0 => Ok(n),
+        _ => Err(MapError::InvalidField { field: field.to_owned(), value: value.to_owned() }),
+    }
+}
+
+// Flood fill numa grade crua (linha por linha), pra quem ainda não tem um Map (gerador).
+pub fn flood_fill(
+    width: usize,
+    height: usize,
+    tiles: &[char],
+    start: (usize, usize),
+    passable: impl Fn(char) -> bool,
+) -> Vec<bool> {
+    let mut reached = vec![false; width * height];
+    let mut stack = vec![start];
+
+    while let Some((col, row)) = stack.pop() {
+        let idx = row * width + col;
+        if reached[idx] || !passable(tiles[idx]) {
+            continue;
+        }
+        reached[idx] = true;
+
+        if col > 0 { stack.push((col - 1, row)); }
+        if row > 0 { stack.push((col, row - 1)); }
+        if col + 1 < width { stack.push((col + 1, row)); }
+        if row + 1 < height { stack.push((col, row + 1)); }
+    }
+    reached
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Texto do mapa e o erro que ele tem que dar
+    type Case = (String, fn(&MapError) -> bool);
+
+    const HEADER: &str = "name: Teste\ntileset: dng/map1.png\nwidth: 4\nheight: 3\n";
+
+    fn map_text(header: &str, grid: &str) -> String {
+        format!("{}---\n{}", header, grid)
+    }
+
+    fn registry() -> TileRegistry {
+        TileRegistry::parse(&std::fs::read_to_string("assets/tiles.cfg").unwrap()).unwrap()
+    }
+
+    #[test]
+    fn parses_a_valid_map() {
+        let registry = registry();
+        let map = Map::parse(&map_text(HEADER, "xxxx\nxs x\nxxxx"), &registry).unwrap();
+        assert_eq!((map.name.as_str(), map.width, map.height), ("Teste", 4, 3));
+        assert_eq!(map.tile(1, 1), 's');
+        assert_eq!(map.tile(2, 1), ' ');
+        assert_eq!(map.tile(-1, 0), ' ');
+        assert_eq!(map.spawn_tiles(&registry), vec![(1, 1)]);
+
+        let shipped = std::fs::read_to_string("assets/maps/sala01.map").unwrap();
+        assert!(Map::parse(&shipped, &registry).is_ok());
+    }
+
+    #[test]
+    fn each_error_variant() {
+        let cases: [Case; 9] = [
+            (map_text("name: Teste\ntileset: a.png\nheight: 3\n", "xxxx\nxsox\nxxxx"), |e| {
+                matches!(e, MapError::MissingField("width"))
+            }),
+            (map_text(&HEADER.replace("width: 4", "width: zero"), "xxxx\nxsox\nxxxx"), |e| {
+                matches!(e, MapError::InvalidField { field, value } if field == "width" && value == "zero")
+            }),
+            (map_text(&HEADER.replace("width: 4", "width: 0"), "xxxx\nxsox\nxxxx"), |e| {
+                matches!(e, MapError::InvalidField { field, .. } if field == "width")
+            }),
+            (map_text(&format!("{}cor: azul\n", HEADER), "xxxx\nxsox\nxxxx"), |e| {
+                matches!(e, MapError::InvalidField { field, .. } if field == "cor")
+            }),
+            (HEADER.to_owned(), |e| matches!(e, MapError::MissingGrid)),
+            (map_text(HEADER, "xxxx\nxsox"), |e| matches!(e, MapError::WrongHeight { expected: 3, found: 2 })),
+            (map_text(HEADER, "xxxx\nxsoxx\nxxxx"), |e| {
+                matches!(e, MapError::RaggedRow { row: 1, expected: 4, found: 5 })
+            }),
+            (map_text(HEADER, "xxxx\nxs?x\nxxxx"), |e| {
+                matches!(e, MapError::UnknownTile { row: 1, col: 2, glyph: '?' })
+            }),
+            (map_text(HEADER, "xxxx\nxoox\nxxxx"), |e| matches!(e, MapError::NoSpawn)),
+        ];
+        let registry = registry();
+        for (text, expected) in cases {
+            let error = Map::parse(&text, &registry).err().expect("mapa inválido passou");
+            assert!(expected(&error), "erro inesperado: {}", error);
+        }
+    }
+}
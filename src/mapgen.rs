@@ -0,0 +1,663 @@
+use macroquad::prelude::*;
+use std::fmt;
+use std::ops::RangeInclusive;
+
+use crate::map::{self, Map, MapError};
+use crate::rng::Rng;
+use crate::tiles::{TileRegistry, VOID_TILE};
+
+// --- GERADOR DE MASMORRA ---
+// Gera mapas novos a cada "Novo Jogo" com o mesmo vocabulário de glifos
+// dos arquivos de mapa (x = muro, o = chão, s = spawn/entrada, > = saída).
+// Mesma seed => mesmo mapa, o gerador só usa o Rng recebido.
+pub const WALL: char = 'x';
+pub const FLOOR: char = 'o';
+pub const SPAWN: char = 's';
+pub const STAIRS_DOWN: char = '>';
+pub const STAIRS_UP: char = '<';
+
+const DEFAULT_TILESET: &str = "dng/map1.png";
+const MIN_CAVE_SIZE: usize = 8;
+// Acima disso quase nunca sobra caverna, vira só o fallback do centro
+const MAX_CAVE_FILL: f32 = 0.7;
+
+// --- Layout ---
+// De onde vem o mapa de cada profundidade. Configurado em assets/dungeon.cfg,
+// uma linha por faixa de profundidade:
+//
+//   1-2  bsp    width=48 height=32 min_leaf=10 min_room=4
+//   3    caves  width=60 height=40 fill=0.45 iterations=5
+//   4    file   maps/sala01.map
+//
+// Parâmetros omitidos usam o padrão. Faixas não podem se sobrepor.
+pub enum Layout {
+    File(String),     // Mapa feito à mão, carregado de assets/
+    Bsp(BspConfig),   // Salas e corredores por particionamento binário
+    Caves(CaveConfig), // Cavernas orgânicas por autômato celular
+}
+
+#[derive(Debug, Clone)]
+pub struct BspConfig {
+    pub width: usize,
+    pub height: usize,
+    pub min_leaf: usize, // Menor lado de uma partição (precisa caber sala + borda)
+    pub min_room: usize, // Menor lado de uma sala
+    pub tileset: String,
+}
+
+impl Default for BspConfig {
+    fn default() -> Self {
+        BspConfig {
+            width: 48,
+            height: 32,
+            min_leaf: 10,
+            min_room: 4,
+            tileset: DEFAULT_TILESET.to_owned(),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct CaveConfig {
+    pub width: usize,
+    pub height: usize,
+    pub fill: f32,         // Chance inicial de cada tile ser muro (0.0 a MAX_CAVE_FILL)
+    pub iterations: usize, // Passadas de suavização
+    pub tileset: String,
+}
+
+impl Default for CaveConfig {
+    fn default() -> Self {
+        CaveConfig {
+            width: 60,
+            height: 40,
+            fill: 0.45,
+            iterations: 5,
+            tileset: DEFAULT_TILESET.to_owned(),
+        }
+    }
+}
+
+// --- Erros ---
+#[derive(Debug)]
+pub enum LayoutError {
+    Io { path: String, source: macroquad::Error },
+    Syntax { line: usize, message: String },
+    Overlap { line: usize, depth: u32 }, // Profundidade coberta por duas linhas
+    Gap(u32),                            // Profundidade sem layout antes da última
+}
+
+impl fmt::Display for LayoutError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LayoutError::Io { path, source } => write!(f, "não foi possível ler '{}': {}", path, source),
+            LayoutError::Syntax { line, message } => write!(f, "linha {}: {}", line, message),
+            LayoutError::Overlap { line, depth } => write!(f, "linha {}: profundidade {} já tem layout", line, depth),
+            LayoutError::Gap(depth) => write!(f, "nenhum layout para a profundidade {}", depth),
+        }
+    }
+}
+
+impl std::error::Error for LayoutError {}
+
+// --- Tabela de layouts por profundidade ---
+pub struct LayoutTable {
+    entries: Vec<(RangeInclusive<u32>, Layout)>,
+}
+
+impl LayoutTable {
+    pub async fn load(path: &str) -> Result<Self, LayoutError> {
+        let text = load_string(path).await.map_err(|source| LayoutError::Io {
+            path: path.to_owned(),
+            source,
+        })?;
+        Self::parse(&text)
+    }
+
+    pub fn parse(text: &str) -> Result<Self, LayoutError> {
+        let mut entries: Vec<(RangeInclusive<u32>, Layout)> = Vec::new();
+
+        for (idx, line) in text.lines().enumerate() {
+            let line_no = idx + 1;
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let mut parts = line.split_whitespace();
+            let depths = parse_depths(parts.next().unwrap_or_default()).map_err(|message| syntax(line_no, &message))?;
+            let kind = parts.next().unwrap_or_default();
+            let params: Vec<&str> = parts.collect();
+            let layout = match kind {
+                "file" => match params.as_slice() {
+                    [path] => Layout::File((*path).to_owned()),
+                    _ => return Err(syntax(line_no, "esperava: file <caminho>")),
+                },
+                "bsp" => Layout::Bsp(parse_bsp(&params).map_err(|message| syntax(line_no, &message))?),
+                "caves" => Layout::Caves(parse_caves(&params).map_err(|message| syntax(line_no, &message))?),
+                _ => return Err(syntax(line_no, &format!("layout desconhecido: '{}'", kind))),
+            };
+
+            if let Some(depth) = depths.clone().find(|d| entries.iter().any(|(range, _)| range.contains(d))) {
+                return Err(LayoutError::Overlap { line: line_no, depth });
+            }
+            entries.push((depths, layout));
+        }
+
+        // Os andares vão de 1 até o mais fundo sem buracos
+        let table = LayoutTable { entries };
+        if let Some(depth) = (1..=table.max_depth()).find(|&d| table.layout_for(d).is_none()) {
+            return Err(LayoutError::Gap(depth));
+        }
+        Ok(table)
+    }
+
+    // Profundidade do último andar (0 se a tabela estiver vazia).
+    pub fn max_depth(&self) -> u32 {
+        self.entries.iter().map(|(range, _)| *range.end()).max().unwrap_or(0)
+    }
+
+    // Layout configurado pra profundidade (1 = primeiro andar).
+    pub fn layout_for(&self, depth: u32) -> Option<&Layout> {
+        self.entries
+            .iter()
+            .find(|(range, _)| range.contains(&depth))
+            .map(|(_, layout)| layout)
+    }
+}
+
+impl Layout {
+    // Constrói o mapa desse layout. Arquivos são carregados, o resto é gerado com a seed.
+    pub async fn build(&self, seed: u64, registry: &TileRegistry) -> Result<Map, MapError> {
+        match self {
+            Layout::File(path) => Map::load(path, registry).await,
+            Layout::Bsp(config) => Ok(generate_bsp(seed, config)),
+            Layout::Caves(config) => Ok(generate_caves(seed, config)),
+        }
+    }
+}
+
+// "3" ou "1-4"
+fn parse_depths(s: &str) -> Result<RangeInclusive<u32>, String> {
+    let (low, high) = s.split_once('-').unwrap_or((s, s));
+    match (low.parse::<u32>(), high.parse::<u32>()) {
+        (Ok(low), Ok(high)) if low >= 1 && low <= high => Ok(low..=high),
+        _ => Err(format!("profundidade inválida: '{}' (use 3 ou 1-4)", s)),
+    }
+}
+
+fn parse_bsp(params: &[&str]) -> Result<BspConfig, String> {
+    let mut config = BspConfig::default();
+    for param in params {
+        let Some((key, value)) = param.split_once('=') else {
+            return Err(format!("parâmetro deve ser chave=valor: '{}'", param));
+        };
+        match key {
+            "width" => config.width = parse_size(key, value)?,
+            "height" => config.height = parse_size(key, value)?,
+            "min_leaf" => config.min_leaf = parse_size(key, value)?,
+            "min_room" => config.min_room = parse_size(key, value)?,
+            "tileset" => config.tileset = value.to_owned(),
+            _ => return Err(format!("parâmetro desconhecido: '{}'", key)),
+        }
+    }
+
+    // A sala precisa caber na partição com 1 tile de muro de cada lado
+    if config.min_room < 2 || config.min_leaf < config.min_room + 2 {
+        return Err("precisa de min_room >= 2 e min_leaf >= min_room + 2".to_owned());
+    }
+    if config.width < config.min_leaf || config.height < config.min_leaf {
+        return Err("width/height menores que min_leaf".to_owned());
+    }
+    Ok(config)
+}
+
+fn parse_caves(params: &[&str]) -> Result<CaveConfig, String> {
+    let mut config = CaveConfig::default();
+    for param in params {
+        let Some((key, value)) = param.split_once('=') else {
+            return Err(format!("parâmetro deve ser chave=valor: '{}'", param));
+        };
+        match key {
+            "width" => config.width = parse_size(key, value)?,
+            "height" => config.height = parse_size(key, value)?,
+            "fill" => config.fill = value.parse().map_err(|_| format!("valor inválido para 'fill': '{}'", value))?,
+            "iterations" => config.iterations = parse_size(key, value)?,
+            "tileset" => config.tileset = value.to_owned(),
+            _ => return Err(format!("parâmetro desconhecido: '{}'", key)),
+        }
+    }
+
+    if !(0.0..=MAX_CAVE_FILL).contains(&config.fill) {
+        return Err(format!("fill precisa estar entre 0.0 e {}", MAX_CAVE_FILL));
+    }
+    if config.width < MIN_CAVE_SIZE || config.height < MIN_CAVE_SIZE {
+        return Err(format!("width/height precisam ser pelo menos {}", MIN_CAVE_SIZE));
+    }
+    Ok(config)
+}
+
+fn parse_size(key: &str, value: &str) -> Result<usize, String> {
+    value.parse().map_err(|_| format!("valor inválido para '{}': '{}'", key, value))
+}
+
+fn syntax(line: usize, message: &str) -> LayoutError {
+    LayoutError::Syntax { line, message: message.to_owned() }
+}
+
+// Grade de trabalho do gerador, linha por linha.
+struct Grid {
+    width: usize,
+    height: usize,
+    tiles: Vec<char>,
+}
+
+impl Grid {
+    fn new(width: usize, height: usize, glyph: char) -> Self {
+        Grid { width, height, tiles: vec![glyph; width * height] }
+    }
+
+    fn get(&self, col: usize, row: usize) -> char {
+        self.tiles[row * self.width + col]
+    }
+
+    fn set(&mut self, col: usize, row: usize, glyph: char) {
+        self.tiles[row * self.width + col] = glyph;
+    }
+
+    fn is_border(&self, col: usize, row: usize) -> bool {
+        col == 0 || row == 0 || col == self.width - 1 || row == self.height - 1
+    }
+}
+
+// --- BSP ---
+// Retângulo em tiles. Usado tanto pras partições quanto pras salas.
+#[derive(Debug, Clone, Copy)]
+struct Area {
+    x: usize,
+    y: usize,
+    w: usize,
+    h: usize,
+}
+
+impl Area {
+    fn center(&self) -> (usize, usize) {
+        (self.x + self.w / 2, self.y + self.h / 2)
+    }
+}
+
+pub fn generate_bsp(seed: u64, config: &BspConfig) -> Map {
+    let mut rng = Rng::new(seed);
+    let mut grid = Grid::new(config.width, config.height, WALL);
+    let mut rooms = Vec::new();
+
+    let root = Area { x: 0, y: 0, w: config.width, h: config.height };
+    split(root, config, &mut rng, &mut grid, &mut rooms);
+
+    // Spawn no centro de uma sala qualquer, saída no centro de outra
+    let spawn_index = rng.range(0, rooms.len());
+    let (spawn_col, spawn_row) = rooms[spawn_index].center();
+    grid.set(spawn_col, spawn_row, SPAWN);
+
+    let exit_pos = if rooms.len() > 1 {
+        // Sorteia entre as outras salas (pula a do spawn)
+        let offset = rng.range(1, rooms.len());
+        rooms[(spawn_index + offset) % rooms.len()].center()
+    } else {
+        // Uma sala só: canto oposto ao centro
+        let room = rooms[0];
+        (room.x, room.y)
+    };
+    grid.set(exit_pos.0, exit_pos.1, STAIRS_DOWN);
+
+    let map = Map::from_grid(
+        format!("Masmorra #{}", seed),
+        config.tileset.clone(),
+        config.width,
+        config.height,
+        grid.tiles,
+    );
+
+    // Salas ligadas em árvore => todo chão alcançável a partir do spawn
+    debug_assert!(is_fully_connected(&map, (spawn_col, spawn_row)));
+    map
+}
+
+// Divide a área recursivamente. Folhas viram salas; ao voltar da recursão
+// liga uma sala de cada metade com um corredor. Retorna uma sala da subárvore.
+fn split(area: Area, config: &BspConfig, rng: &mut Rng, grid: &mut Grid, rooms: &mut Vec<Area>) -> Area {
+    let can_split_w = area.w >= config.min_leaf * 2;
+    let can_split_h = area.h >= config.min_leaf * 2;
+
+    if !can_split_w && !can_split_h {
+        let room = carve_room(area, config, rng, grid);
+        rooms.push(room);
+        return room;
+    }
+
+    // Prefere cortar o lado mais comprido pra não gerar salas tripa
+    let vertical = match (can_split_w, can_split_h) {
+        (true, false) => true,
+        (false, true) => false,
+        _ if area.w * 4 > area.h * 5 => true,
+        _ if area.h * 4 > area.w * 5 => false,
+        _ => rng.chance(0.5),
+    };
+
+    let (first, second) = if vertical {
+        let cut = rng.range(config.min_leaf, area.w - config.min_leaf + 1);
+        (
+            Area { w: cut, ..area },
+            Area { x: area.x + cut, w: area.w - cut, ..area },
+        )
+    } else {
+        let cut = rng.range(config.min_leaf, area.h - config.min_leaf + 1);
+        (
+            Area { h: cut, ..area },
+            Area { y: area.y + cut, h: area.h - cut, ..area },
+        )
+    };
+
+    let room_a = split(first, config, rng, grid, rooms);
+    let room_b = split(second, config, rng, grid, rooms);
+    carve_corridor(room_a.center(), room_b.center(), rng, grid);
+
+    if rng.chance(0.5) { room_a } else { room_b }
+}
+
+// Sala de tamanho aleatório dentro da folha, sempre com 1 tile de muro em volta.
+fn carve_room(leaf: Area, config: &BspConfig, rng: &mut Rng, grid: &mut Grid) -> Area {
+    let w = rng.range(config.min_room, leaf.w - 2 + 1);
+    let h = rng.range(config.min_room, leaf.h - 2 + 1);
+    let x = rng.range(leaf.x + 1, leaf.x + leaf.w - 1 - w + 1);
+    let y = rng.range(leaf.y + 1, leaf.y + leaf.h - 1 - h + 1);
+
+    for row in y..y + h {
+        for col in x..x + w {
+            grid.set(col, row, FLOOR);
+        }
+    }
+    Area { x, y, w, h }
+}
+
+// Corredor em L entre dois pontos. Sorteia se começa pela horizontal ou vertical.
+fn carve_corridor(from: (usize, usize), to: (usize, usize), rng: &mut Rng, grid: &mut Grid) {
+    let (x1, y1) = from;
+    let (x2, y2) = to;
+    let corner = if rng.chance(0.5) { (x2, y1) } else { (x1, y2) };
+
+    for (a, b) in [(from, corner), (corner, to)] {
+        for row in a.1.min(b.1)..=a.1.max(b.1) {
+            for col in a.0.min(b.0)..=a.0.max(b.0) {
+                grid.set(col, row, FLOOR);
+            }
+        }
+    }
+}
+
+// Todo tile que não é muro/vazio precisa ser alcançável a partir de start.
+pub fn is_fully_connected(map: &Map, start: (usize, usize)) -> bool {
+    let passable = |glyph: char| glyph != WALL && glyph != VOID_TILE;
+    let reached = map.flood_fill(start, passable);
+    map.tiles()
+        .all(|(col, row, glyph)| !passable(glyph) || reached[row * map.width + col])
+}
+
+// --- CAVERNAS ---
+// Autômato celular: preenchimento aleatório, N passadas de suavização,
+// fica só a maior região conectada e o spawn vai num chão dela.
+const MAX_CAVE_ATTEMPTS: usize = 10;
+const MIN_CAVE_REGION: usize = 9;
+
+pub fn generate_caves(seed: u64, config: &CaveConfig) -> Map {
+    let mut rng = Rng::new(seed);
+
+    // Se a maior região ficar pequena demais tenta de novo. O Rng continua
+    // de onde parou, então a mesma seed ainda gera a mesma caverna.
+    let min_region = config.width * config.height / 5;
+    let mut best: Option<(Grid, Vec<(usize, usize)>)> = None;
+    for _ in 0..MAX_CAVE_ATTEMPTS {
+        let mut grid = random_fill(config, &mut rng);
+        for _ in 0..config.iterations {
+            grid = smooth(&grid);
+        }
+        let region = largest_region(&grid);
+
+        let good_enough = region.len() >= min_region;
+        if best.as_ref().is_none_or(|(_, best_region)| region.len() > best_region.len()) {
+            best = Some((grid, region));
+        }
+        if good_enough {
+            break;
+        }
+    }
+    let (mut grid, mut region) = best.expect("MAX_CAVE_ATTEMPTS > 0");
+
+    // Caso extremo (fill alto demais): sobrou quase nenhum chão. Abre uma sala
+    // 3x3 no centro na marra e fica só com ela, pra nunca prender o jogador num tile.
+    if region.len() < MIN_CAVE_REGION {
+        let (center_col, center_row) = (config.width / 2, config.height / 2);
+        region.clear();
+        for row in center_row - 1..=center_row + 1 {
+            for col in center_col - 1..=center_col + 1 {
+                grid.set(col, row, FLOOR);
+                region.push((col, row));
+            }
+        }
+    }
+
+    // Tudo que não é da maior região vira muro
+    let mut keep = vec![false; config.width * config.height];
+    for &(col, row) in &region {
+        keep[row * config.width + col] = true;
+    }
+    for (idx, glyph) in grid.tiles.iter_mut().enumerate() {
+        if !keep[idx] {
+            *glyph = WALL;
+        }
+    }
+
+    let spawn_index = rng.range(0, region.len());
+    let (spawn_col, spawn_row) = region[spawn_index];
+    grid.set(spawn_col, spawn_row, SPAWN);
+
+    // Saída em outro tile da região (tem pelo menos MIN_CAVE_REGION tiles)
+    let offset = rng.range(1, region.len());
+    let (exit_col, exit_row) = region[(spawn_index + offset) % region.len()];
+    grid.set(exit_col, exit_row, STAIRS_DOWN);
+
+    let map = Map::from_grid(
+        format!("Caverna #{}", seed),
+        config.tileset.clone(),
+        config.width,
+        config.height,
+        grid.tiles,
+    );
+    debug_assert!(is_fully_connected(&map, (spawn_col, spawn_row)));
+    map
+}
+
+// Borda sempre muro, o resto é muro com chance fill.
+fn random_fill(config: &CaveConfig, rng: &mut Rng) -> Grid {
+    let mut grid = Grid::new(config.width, config.height, WALL);
+    for row in 0..config.height {
+        for col in 0..config.width {
+            if !grid.is_border(col, row) && !rng.chance(config.fill) {
+                grid.set(col, row, FLOOR);
+            }
+        }
+    }
+    grid
+}
+
+// Regra 4-5: mais de 4 vizinhos muro vira muro, menos de 4 vira chão, 4 fica como está.
+// Fora do mapa conta como muro, pra caverna não vazar pela borda.
+fn smooth(grid: &Grid) -> Grid {
+    let mut next = Grid::new(grid.width, grid.height, WALL);
+    for row in 0..grid.height {
+        for col in 0..grid.width {
+            if grid.is_border(col, row) {
+                continue;
+            }
+            let walls = wall_neighbors(grid, col, row);
+            let glyph = match walls {
+                0..=3 => FLOOR,
+                4 => grid.get(col, row),
+                _ => WALL,
+            };
+            next.set(col, row, glyph);
+        }
+    }
+    next
+}
+
+fn wall_neighbors(grid: &Grid, col: usize, row: usize) -> usize {
+    let mut count = 0;
+    for dy in -1..=1isize {
+        for dx in -1..=1isize {
+            if dx == 0 && dy == 0 {
+                continue;
+            }
+            let (c, r) = (col as isize + dx, row as isize + dy);
+            let outside = c < 0 || r < 0 || c >= grid.width as isize || r >= grid.height as isize;
+            if outside || grid.get(c as usize, r as usize) == WALL {
+                count += 1;
+            }
+        }
+    }
+    count
+}
+
+// Tiles (col, row) da maior região de chão conectada (4 direções).
+fn largest_region(grid: &Grid) -> Vec<(usize, usize)> {
+    let passable = |glyph: char| glyph == FLOOR;
+    let mut visited = vec![false; grid.tiles.len()];
+    let mut largest = Vec::new();
+
+    for start in 0..grid.tiles.len() {
+        if visited[start] || !passable(grid.tiles[start]) {
+            continue;
+        }
+        let start_pos = (start % grid.width, start / grid.width);
+        let reached = map::flood_fill(grid.width, grid.height, &grid.tiles, start_pos, passable);
+
+        let mut region = Vec::new();
+        for (idx, &hit) in reached.iter().enumerate() {
+            if hit {
+                visited[idx] = true;
+                region.push((idx % grid.width, idx / grid.width));
+            }
+        }
+        if region.len() > largest.len() {
+            largest = region;
+        }
+    }
+    largest
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SEEDS: [u64; 5] = [0, 1, 42, 1_700_000_000_000, u64::MAX];
+
+    fn glyphs(map: &Map) -> Vec<char> {
+        map.tiles().map(|(_, _, glyph)| glyph).collect()
+    }
+
+    fn positions(map: &Map, wanted: char) -> Vec<(usize, usize)> {
+        map.tiles().filter(|&(_, _, glyph)| glyph == wanted).map(|(col, row, _)| (col, row)).collect()
+    }
+
+    fn bsp_configs() -> [BspConfig; 2] {
+        let big = BspConfig { width: 64, height: 40, min_leaf: 12, min_room: 5, ..BspConfig::default() };
+        [BspConfig::default(), big]
+    }
+
+    #[test]
+    fn bsp_same_seed_same_map() {
+        for config in bsp_configs() {
+            for seed in SEEDS {
+                assert_eq!(glyphs(&generate_bsp(seed, &config)), glyphs(&generate_bsp(seed, &config)));
+            }
+            assert_ne!(glyphs(&generate_bsp(1, &config)), glyphs(&generate_bsp(2, &config)));
+        }
+    }
+
+    #[test]
+    fn bsp_one_spawn_and_everything_reachable() {
+        for config in bsp_configs() {
+            for seed in SEEDS {
+                let map = generate_bsp(seed, &config);
+                let spawns = positions(&map, SPAWN);
+                assert_eq!(spawns.len(), 1, "seed {}", seed);
+                assert!(is_fully_connected(&map, spawns[0]), "seed {}", seed);
+                assert_eq!(positions(&map, STAIRS_DOWN).len(), 1, "seed {}", seed);
+            }
+        }
+    }
+
+
+    fn cave_configs() -> [CaveConfig; 2] {
+        let big = CaveConfig { width: 72, height: 48, fill: 0.48, iterations: 4, ..CaveConfig::default() };
+        [CaveConfig::default(), big]
+    }
+
+    #[test]
+    fn largest_region_picks_the_biggest() {
+        // Duas salas separadas por muro: a da direita (3x2) é maior que a da esquerda (1x2)
+        let rows = ["xxxxxxx", "xoxooox", "xoxooox", "xxxxxxx"];
+        let tiles: Vec<char> = rows.iter().flat_map(|row| row.chars()).collect();
+        let grid = Grid { width: 7, height: 4, tiles };
+        let mut region = largest_region(&grid);
+        region.sort();
+        assert_eq!(region, vec![(3, 1), (3, 2), (4, 1), (4, 2), (5, 1), (5, 2)]);
+    }
+
+    #[test]
+    fn caves_same_seed_same_map() {
+        for config in cave_configs() {
+            for seed in SEEDS {
+                assert_eq!(glyphs(&generate_caves(seed, &config)), glyphs(&generate_caves(seed, &config)));
+            }
+        }
+    }
+
+    #[test]
+    fn caves_keep_one_region_with_the_spawn() {
+        for config in cave_configs() {
+            for seed in SEEDS {
+                let map = generate_caves(seed, &config);
+                let spawns = positions(&map, SPAWN);
+                assert_eq!(spawns.len(), 1, "seed {}", seed);
+                // Conectado a partir do spawn = sobrou uma região só e o spawn está nela
+                assert!(is_fully_connected(&map, spawns[0]), "seed {}", seed);
+                assert_eq!(positions(&map, STAIRS_DOWN).len(), 1, "seed {}", seed);
+                let open = map.tiles().filter(|&(_, _, glyph)| glyph != WALL).count();
+                assert!(open >= config.width * config.height / 5, "seed {}: só {} tiles", seed, open);
+            }
+        }
+    }
+
+    #[test]
+    fn caves_all_wall_still_get_a_room() {
+        let config = CaveConfig { width: 20, height: 20, fill: 1.0, ..CaveConfig::default() };
+        let map = generate_caves(7, &config);
+        let spawns = positions(&map, SPAWN);
+        assert_eq!(spawns.len(), 1);
+        let (col, row) = spawns[0];
+        assert!((9..=11).contains(&col) && (9..=11).contains(&row));
+        let open = map.tiles().filter(|&(_, _, glyph)| glyph != WALL).count();
+        assert_eq!(open, MIN_CAVE_REGION);
+        assert_eq!(positions(&map, STAIRS_DOWN).len(), 1);
+        assert!(is_fully_connected(&map, spawns[0]));
+    }
+
+    #[test]
+    fn caves_reject_fill_too_high() {
+        assert!(parse_caves(&["fill=0.7"]).is_ok());
+        for fill in ["fill=0.71", "fill=0.999", "fill=-0.1"] {
+            assert!(parse_caves(&[fill]).is_err(), "{}", fill);
+        }
+    }
+}
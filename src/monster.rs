@@ -0,0 +1,262 @@
+use macroquad::prelude::*;
+use serde::Deserialize;
+
+use crate::combat::Stats;
+use crate::content::{self, Rarity};
+use crate::loot::{DepthRange, Table};
+use crate::fov::TileMask;
+use crate::game::{can_enter, NEIGHBORS};
+use crate::item::ItemKind;
+use crate::map::Map;
+use crate::mapgen::{FLOOR, SPAWN, STAIRS_UP};
+use crate::motion::Motion;
+use crate::path::{self, Connectivity, DijkstraMap};
+use crate::rng::Rng;
+use crate::status::{StatusEffect, StatusKind, Statuses, CONFUSION_CHANCE};
+use crate::tiles::{TileFlag, TileRegistry};
+use crate::turn::Energy;
+
+// --- MONSTROS ---
+// Tipos de monstro vêm de assets/monsters.ron (ver content.rs). Cada um tem um
+// glifo, que é o marcador usado nos mapas feitos à mão (ver tiles.cfg, flag
+// monster), e um sprite em assets/sprites/monsters.png (quadros TILE_SIZE x TILE_SIZE).
+// Faction é o "ai" no arquivo.
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+pub enum Faction {
+    Hostile, // Persegue o jogador quando vê ele
+    Neutral, // Só vagueia, ignora o jogador
+}
+
+#[derive(Debug)]
+pub struct MonsterKind {
+    pub name: &'static str,
+    pub glyph: char,
+    pub sprite: Rect,  // Pedaço do monsters.png
+    pub stats: Stats,  // Com a vida cheia
+    pub speed: i32,    // Mesma escala do jogador (NORMAL_SPEED = 10)
+    pub faction: Faction,
+    pub on_hit: Option<StatusEffect>, // Efeito que o ataque deixa no jogador quando acerta
+    pub drops: Option<Table<&'static ItemKind>>, // O que cai no chão quando morre (ver loot.rs)
+    pub xp: u32, // Quanto o jogador ganha quando ele morre (ver level.rs)
+    pub depth: DepthRange, // Andares em que aparece no gerador sem tabela
+    pub rarity: Rarity,    // Peso no sorteio do gerador
+}
+
+// Quantos monstros o gerador espalha sem tabela: MONSTERS_BASE + profundidade
+const MONSTERS_BASE: usize = 2;
+// Distância mínima (em passos andando) da entrada pra não nascer nada em cima do jogador
+const SAFE_RADIUS: u32 = 6;
+// Custo no A* de passar por um tile com outro monstro: contorna se o desvio for curto
+const CROWD_COST: u32 = 5;
+// Mistura na seed do andar pra o sorteio dos monstros não repetir o do mapa
+const SPAWN_SALT: u64 = 0x6D6F_6E73_7465_7273;
+
+pub fn kind_for_glyph(glyph: char) -> Option<&'static MonsterKind> {
+    content::monsters().iter().find(|kind| kind.glyph == glyph)
+}
+
+// --- IA ---
+// O que o monstro resolveu fazer no turno dele
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Action {
+    Move { col: usize, row: usize },
+    Attack, // Ataca o jogador (só quando está do lado)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AiState {
+    Wander,                              // Não sabe onde o jogador está
+    Chase { last_seen: (usize, usize) }, // Indo pra onde viu o jogador por último
+}
+
+pub struct Monster {
+    pub kind: &'static MonsterKind,
+    // Igual ao Player: o tile é a verdade, o motion é o desenho
+    pub col: usize,
+    pub row: usize,
+    pub motion: Motion,
+    pub stats: Stats,
+    pub energy: Energy,
+    pub ai: AiState,
+    pub status: Statuses, // Efeitos com tempo (ver status.rs)
+}
+
+impl Monster {
+    pub fn new(kind: &'static MonsterKind, col: usize, row: usize) -> Self {
+        Monster {
+            kind,
+            col,
+            row,
+            motion: Motion::at(col, row),
+            stats: kind.stats,
+            energy: Energy::new(kind.speed),
+            ai: AiState::Wander,
+            status: Statuses::default(),
+        }
+    }
+
+    // Chamar depois de mexer nos efeitos: pressa e lentidão mudam a velocidade
+    pub fn refresh_speed(&mut self) {
+        self.energy.speed = (self.kind.speed + self.status.speed_bonus()).max(1);
+    }
+
+    pub fn step_to(&mut self, col: usize, row: usize) {
+        self.col = col;
+        self.row = row;
+        self.motion.slide_to(col, row);
+    }
+
+    pub fn source(&self) -> Rect {
+        self.kind.sprite
+    }
+
+    // Decide a próxima ação. Quem enxerga o jogador também é visto por ele,
+    // então "o jogador está à vista" = o tile do monstro está no FOV do jogador.
+    // Cego, só nota o jogador colado nele. Confuso, às vezes tropeça pra um lado qualquer.
+    // occupied diz se tem alguém (outro monstro) no tile.
+    pub fn choose_action(
+        &mut self,
+        map: &Map,
+        registry: &TileRegistry,
+        visible: &TileMask,
+        player: (usize, usize),
+        occupied: impl Fn(usize, usize) -> bool,
+        rng: &mut Rng,
+    ) -> Option<Action> {
+        if self.status.has(StatusKind::Confusion) && rng.chance(CONFUSION_CHANCE) {
+            let here = (self.col as isize, self.row as isize);
+            let (dx, dy) = NEIGHBORS[rng.range(0, NEIGHBORS.len())];
+            let to = (here.0 + dx, here.1 + dy);
+            let tile = (to.0 as usize, to.1 as usize);
+            // Bateu em parede, em outro monstro ou no jogador: perde o turno
+            if !can_enter(map, registry, here, to) || tile == player || occupied(tile.0, tile.1) {
+                return None;
+            }
+            return Some(Action::Move { col: tile.0, row: tile.1 });
+        }
+
+        let sees_player = visible.get(self.col, self.row)
+            && (!self.status.has(StatusKind::Blindness) || steps((self.col, self.row), player) == 1);
+        if self.kind.faction == Faction::Hostile && sees_player {
+            self.ai = AiState::Chase { last_seen: player };
+            // Do lado do jogador (diagonal também, se a quina deixar): em vez de andar, ataca
+            let here = (self.col as isize, self.row as isize);
+            if steps((self.col, self.row), player) == 1
+                && can_enter(map, registry, here, (player.0 as isize, player.1 as isize))
+            {
+                return Some(Action::Attack);
+            }
+        }
+
+        match self.ai {
+            AiState::Chase { last_seen } => {
+                if (self.col, self.row) == last_seen {
+                    // Chegou onde viu o jogador e não achou nada
+                    self.ai = AiState::Wander;
+                    return None;
+                }
+                // A* até onde viu o jogador. Outro monstro no caminho não bloqueia, só
+                // encarece: se der pra contornar contorna, senão espera ele sair.
+                let cost = |from: (usize, usize), to: (usize, usize)| {
+                    let passable = can_enter(map, registry, (from.0 as isize, from.1 as isize), (to.0 as isize, to.1 as isize));
+                    passable.then_some(if occupied(to.0, to.1) { CROWD_COST } else { 1 })
+                };
+                let route = path::astar(map.width, map.height, (self.col, self.row), last_seen, Connectivity::Eight, cost);
+                match route.as_ref().and_then(|route| route.first()) {
+                    Some(&(col, row)) if (col, row) != player && !occupied(col, row) => Some(Action::Move { col, row }),
+                    Some(_) => None, // Caminho tapado por alguém, espera
+                    None => {
+                        // Sem caminho: desiste e volta a vagar
+                        if !sees_player {
+                            self.ai = AiState::Wander;
+                        }
+                        None
+                    }
+                }
+            }
+            AiState::Wander => {
+                // Vizinhos livres (8 direções): andável pela mesma regra do jogador, sem ninguém em cima
+                let here = (self.col as isize, self.row as isize);
+                let open: Vec<(usize, usize)> = NEIGHBORS
+                    .iter()
+                    .map(|&(dx, dy)| (here.0 + dx, here.1 + dy))
+                    .filter(|&to| can_enter(map, registry, here, to))
+                    .map(|(col, row)| (col as usize, row as usize))
+                    .filter(|&tile| tile != player && !occupied(tile.0, tile.1))
+                    .collect();
+
+                // Um passo pra um lado qualquer, às vezes fica parado
+                if open.is_empty() || rng.chance(0.3) {
+                    None
+                } else {
+                    let (col, row) = open[rng.range(0, open.len())];
+                    Some(Action::Move { col, row })
+                }
+            }
+        }
+    }
+}
+
+// Passos até lá andando em 8 direções (diagonal conta 1)
+fn steps(a: (usize, usize), b: (usize, usize)) -> usize {
+    a.0.abs_diff(b.0).max(a.1.abs_diff(b.1))
+}
+
+// --- Spawn ---
+// Monstros de um andar novo. Se o mapa tem marcadores (tiles com a flag monster)
+// cada marcador vira um monstro do tipo daquele glifo e o tile vira chão.
+// Sem marcadores (mapas gerados) espalha em chão longe da entrada o que sair
+// da tabela da profundidade (spawns.ron). Sem tabela, MONSTERS_BASE + profundidade
+// sorteados pela raridade entre os tipos que cabem na profundidade.
+pub fn spawn_monsters(map: &mut Map, depth: u32, seed: u64, registry: &TileRegistry) -> Vec<Monster> {
+    let mut rng = Rng::new(seed ^ SPAWN_SALT);
+    let available: Vec<&'static MonsterKind> = content::monsters().iter().filter(|kind| kind.depth.contains(depth)).collect();
+
+    let markers: Vec<(usize, usize, char)> = map
+        .tiles()
+        .filter(|&(_, _, glyph)| registry.has_flag(glyph, TileFlag::Monster))
+        .collect();
+
+    if !markers.is_empty() {
+        let mut monsters = Vec::new();
+        for (col, row, glyph) in markers {
+            // Marcador sem tipo próprio: sorteia um que cabe na profundidade (se tiver algum)
+            let kind = kind_for_glyph(glyph).or_else(|| rng.pick_weighted(&available, |kind| kind.rarity.weight()).copied());
+            map.set(col, row, FLOOR);
+            if let Some(kind) = kind {
+                monsters.push(Monster::new(kind, col, row));
+            }
+        }
+        return monsters;
+    }
+
+    // Distância andando (só retas) até a entrada: monstro não nasce colado no jogador
+    let entrances = map.find(SPAWN).into_iter().chain(map.find(STAIRS_UP));
+    let cost = |from: (usize, usize), to: (usize, usize)| {
+        can_enter(map, registry, (from.0 as isize, from.1 as isize), (to.0 as isize, to.1 as isize)).then_some(1)
+    };
+    let from_entrance = DijkstraMap::new(map.width, map.height, entrances, Connectivity::Four, cost);
+    let mut candidates: Vec<(usize, usize)> = map
+        .tiles()
+        .filter(|&(_, _, glyph)| glyph == FLOOR)
+        .map(|(col, row, _)| (col, row))
+        .filter(|&(col, row)| from_entrance.get(col, row).is_none_or(|d| d >= SAFE_RADIUS))
+        .collect();
+
+    let kinds: Vec<&'static MonsterKind> = match content::spawns().monsters.get(depth) {
+        Some(table) => table.roll(&mut rng),
+        None => (0..MONSTERS_BASE + depth as usize)
+            .filter_map(|_| rng.pick_weighted(&available, |kind| kind.rarity.weight()).copied())
+            .collect(),
+    };
+    let mut monsters = Vec::new();
+    for kind in kinds {
+        // Sem lugar longe da entrada: o que sobrou não aparece
+        if candidates.is_empty() {
+            break;
+        }
+        let (col, row) = candidates.swap_remove(rng.range(0, candidates.len()));
+        monsters.push(Monster::new(kind, col, row));
+    }
+    monsters
+}
@@ -0,0 +1,72 @@
+use crate::{tile_center, MOVE_DURATION};
+
+// --- ANIMAÇÃO DE MOVIMENTO ---
+// Só apresentação: a posição "de verdade" de quem anda é o tile (col, row).
+// Aqui fica a posição em pixels que desliza de um tile pro outro em MOVE_DURATION.
+pub struct Motion {
+    // Posição x/y
+    pub x: f32,
+    pub y: f32,
+
+    start_x: f32,  // Posição X de onde o movimento começou
+    start_y: f32,  // Posição Y de onde o movimento começou
+    target_x: f32, // Posição X para onde está indo
+    target_y: f32, // Posição Y para onde está indo
+
+    timer: f32, // Contador de tempo para a animação de movimento (restante)
+}
+
+impl Motion {
+    // Parado no centro do tile (col, row)
+    pub fn at(col: usize, row: usize) -> Self {
+        let (x, y) = tile_center(col, row);
+        Motion {
+            x,
+            y,
+            start_x: x,
+            start_y: y,
+            target_x: x,
+            target_y: y,
+            timer: 0.0,
+        }
+    }
+
+    // Começa a deslizar da posição atual até o centro de (col, row)
+    pub fn slide_to(&mut self, col: usize, row: usize) {
+        let (x, y) = tile_center(col, row);
+        self.start_x = self.x; // Posição de partida é a atual
+        self.start_y = self.y;
+        self.target_x = x; // Novo alvo (centro do próximo tile)
+        self.target_y = y;
+        self.timer = MOVE_DURATION;
+    }
+
+    pub fn is_moving(&self) -> bool {
+        self.timer > 0.0
+    }
+
+    // Avança a animação. Retorna true no frame em que ela termina.
+    pub fn update(&mut self, delta_time: f32) -> bool {
+        if self.timer <= 0.0 {
+            return false;
+        }
+
+        // Está se movendo, diminui o timer
+        self.timer = (self.timer - delta_time).max(0.0);
+
+        if self.timer <= 0.0 {
+            // Movimento finalizado. Trava a posição no alvo.
+            self.x = self.target_x;
+            self.y = self.target_y;
+            true
+        } else {
+            // Interpolação Linear (LERP)
+            let t = 1.0 - (self.timer / MOVE_DURATION); // Progresso de 0.0 a 1.0
+
+            // Interpola a posição (x, y) entre start e target
+            self.x = self.start_x + (self.target_x - self.start_x) * t;
+            self.y = self.start_y + (self.target_y - self.start_y) * t;
+            false
+        }
+    }
+}
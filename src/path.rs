@@ -0,0 +1,259 @@
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+// --- PATHFINDING ---
+// Caminhos numa grade width * height:
+//   astar: caminho mais barato de um tile até outro
+//   DijkstraMap: distância de todo tile até a fonte mais próxima (várias fontes)
+//
+// Igual ao fov.rs, não depende de Map nem do macroquad: quem chama passa o
+// tamanho da grade e uma função cost(from, to) com o custo de dar um passo de
+// from pro vizinho to (None = não dá pra passar). É ali que entram colisão,
+// regra de quinas e custos por tile. Custo mínimo de um passo é 1 (menos que
+// isso vira 1), senão a heurística do A* deixa de valer.
+
+// Vizinhos considerados em cada passo
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Connectivity {
+    Four,  // Só retas
+    Eight, // Retas e diagonais (diagonal custa um passo, igual reta)
+}
+
+const STRAIGHT: [(isize, isize); 4] = [(1, 0), (-1, 0), (0, 1), (0, -1)];
+const ALL_DIRECTIONS: [(isize, isize); 8] = [(1, 0), (-1, 0), (0, 1), (0, -1), (1, 1), (1, -1), (-1, 1), (-1, -1)];
+
+impl Connectivity {
+    fn offsets(self) -> &'static [(isize, isize)] {
+        match self {
+            Connectivity::Four => &STRAIGHT,
+            Connectivity::Eight => &ALL_DIRECTIONS,
+        }
+    }
+
+    // Menor número de passos entre a e b sem obstáculos (heurística do A*)
+    fn steps(self, a: (usize, usize), b: (usize, usize)) -> u32 {
+        let (dx, dy) = (a.0.abs_diff(b.0) as u32, a.1.abs_diff(b.1) as u32);
+        match self {
+            Connectivity::Four => dx + dy,
+            Connectivity::Eight => dx.max(dy),
+        }
+    }
+}
+
+// Vizinhos de tile dentro da grade
+fn neighbors(
+    width: usize,
+    height: usize,
+    tile: (usize, usize),
+    connectivity: Connectivity,
+) -> impl Iterator<Item = (usize, usize)> {
+    connectivity.offsets().iter().filter_map(move |&(dx, dy)| {
+        let col = tile.0.checked_add_signed(dx)?;
+        let row = tile.1.checked_add_signed(dy)?;
+        (col < width && row < height).then_some((col, row))
+    })
+}
+
+// --- A* ---
+// Caminho mais barato de start até goal. Retorna os passos em ordem, sem o
+// start e com o goal no fim (vazio se start == goal). None se não tem caminho.
+pub fn astar(
+    width: usize,
+    height: usize,
+    start: (usize, usize),
+    goal: (usize, usize),
+    connectivity: Connectivity,
+    cost: impl Fn((usize, usize), (usize, usize)) -> Option<u32>,
+) -> Option<Vec<(usize, usize)>> {
+    let index = |(col, row): (usize, usize)| row * width + col;
+    if start.0 >= width || start.1 >= height || goal.0 >= width || goal.1 >= height {
+        return None;
+    }
+
+    let mut best = vec![u32::MAX; width * height]; // Menor custo achado até cada tile
+    let mut came_from: Vec<Option<(usize, usize)>> = vec![None; width * height];
+    let mut open = BinaryHeap::new();
+    best[index(start)] = 0;
+    // Ordena por custo estimado total; empate vai pra quem está mais perto do goal
+    open.push(Reverse((connectivity.steps(start, goal), connectivity.steps(start, goal), start)));
+
+    while let Some(Reverse((_, _, tile))) = open.pop() {
+        if tile == goal {
+            let mut path = Vec::new();
+            let mut current = goal;
+            while current != start {
+                path.push(current);
+                current = came_from[index(current)].expect("tile alcançado tem origem");
+            }
+            path.reverse();
+            return Some(path);
+        }
+        let spent = best[index(tile)];
+        for next in neighbors(width, height, tile, connectivity) {
+            let Some(step) = cost(tile, next) else {
+                continue;
+            };
+            let total = spent + step.max(1);
+            if total < best[index(next)] {
+                best[index(next)] = total;
+                came_from[index(next)] = Some(tile);
+                let remaining = connectivity.steps(next, goal);
+                open.push(Reverse((total + remaining, remaining, next)));
+            }
+        }
+    }
+    None
+}
+
+// --- Dijkstra ---
+// Campo de distâncias: pra cada tile, o custo até a fonte mais próxima.
+// Serve pra "longe de todas as entradas", "rumo ao inexplorado mais perto"...
+// O custo é do passo saindo das fontes (from -> to); com custos simétricos
+// é o mesmo que andar até elas.
+pub struct DijkstraMap {
+    width: usize,
+    height: usize,
+    distances: Vec<Option<u32>>,          // None = nenhuma fonte alcança
+    parents: Vec<Option<(usize, usize)>>, // Tile anterior, um passo mais perto da fonte
+}
+
+impl DijkstraMap {
+    pub fn new(
+        width: usize,
+        height: usize,
+        sources: impl IntoIterator<Item = (usize, usize)>,
+        connectivity: Connectivity,
+        cost: impl Fn((usize, usize), (usize, usize)) -> Option<u32>,
+    ) -> Self {
+        let index = |(col, row): (usize, usize)| row * width + col;
+        let mut distances = vec![None; width * height];
+        let mut parents = vec![None; width * height];
+        let mut open = BinaryHeap::new();
+        for source in sources {
+            if source.0 < width && source.1 < height {
+                distances[index(source)] = Some(0);
+                open.push(Reverse((0, source)));
+            }
+        }
+
+        while let Some(Reverse((spent, tile))) = open.pop() {
+            if distances[index(tile)].is_some_and(|d| d < spent) {
+                continue; // Já saiu da fila por um caminho mais barato
+            }
+            for next in neighbors(width, height, tile, connectivity) {
+                let Some(step) = cost(tile, next) else {
+                    continue;
+                };
+                let total = spent + step.max(1);
+                if distances[index(next)].is_none_or(|d| total < d) {
+                    distances[index(next)] = Some(total);
+                    parents[index(next)] = Some(tile);
+                    open.push(Reverse((total, next)));
+                }
+            }
+        }
+
+        DijkstraMap { width, height, distances, parents }
+    }
+
+    // Custo de (col, row) até a fonte mais próxima. None se inalcançável ou fora da grade.
+    pub fn get(&self, col: usize, row: usize) -> Option<u32> {
+        if col >= self.width || row >= self.height {
+            return None;
+        }
+        self.distances[row * self.width + col]
+    }
+
+    // Caminho de tile até a fonte mais próxima: passos em ordem, sem o tile e
+    // com a fonte no fim (vazio se tile já é fonte). None se inalcançável.
+    pub fn path_from(&self, tile: (usize, usize)) -> Option<Vec<(usize, usize)>> {
+        self.get(tile.0, tile.1)?;
+        let mut path = Vec::new();
+        let mut current = tile;
+        while let Some(parent) = self.parents[current.1 * self.width + current.0] {
+            path.push(parent);
+            current = parent;
+        }
+        Some(path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Grade à mão: '#' não passa, '~' custa 10 (lama), o resto custa 1.
+    struct Grid {
+        width: usize,
+        height: usize,
+        tiles: Vec<Vec<char>>,
+    }
+
+    impl Grid {
+        fn new(rows: &[&str]) -> Self {
+            let tiles: Vec<Vec<char>> = rows.iter().map(|row| row.chars().collect()).collect();
+            Grid { width: tiles[0].len(), height: tiles.len(), tiles }
+        }
+
+        fn cost(&self) -> impl Fn((usize, usize), (usize, usize)) -> Option<u32> + '_ {
+            |_, (col, row)| match self.tiles[row][col] {
+                '#' => None,
+                '~' => Some(10),
+                _ => Some(1),
+            }
+        }
+
+        fn astar(&self, start: (usize, usize), goal: (usize, usize), connectivity: Connectivity) -> Option<Vec<(usize, usize)>> {
+            astar(self.width, self.height, start, goal, connectivity, self.cost())
+        }
+    }
+
+    #[test]
+    fn four_vs_eight_connectivity() {
+        let grid = Grid::new(&[".....", ".....", ".....", ".....", "....."]);
+        let four = grid.astar((0, 0), (4, 4), Connectivity::Four).unwrap();
+        let eight = grid.astar((0, 0), (4, 4), Connectivity::Eight).unwrap();
+        assert_eq!(four.len(), 8);
+        assert_eq!(eight, vec![(1, 1), (2, 2), (3, 3), (4, 4)]);
+        // Só retas: cada passo muda uma coordenada só
+        let mut previous: (usize, usize) = (0, 0);
+        for &step in &four {
+            assert_eq!(previous.0.abs_diff(step.0) + previous.1.abs_diff(step.1), 1);
+            previous = step;
+        }
+        assert_eq!(grid.astar((2, 2), (2, 2), Connectivity::Eight), Some(vec![]));
+    }
+
+    #[test]
+    fn weighted_cost_takes_the_cheaper_detour() {
+        let grid = Grid::new(&[".....", ".~~~.", "....."]);
+        let path = grid.astar((0, 1), (4, 1), Connectivity::Four).unwrap();
+        // Reto pela lama custaria 31, a volta por cima ou por baixo custa 6
+        assert_eq!(path.len(), 6);
+        assert!(path.iter().all(|&(col, row)| grid.tiles[row][col] != '~'));
+        assert_eq!(path.last(), Some(&(4, 1)));
+    }
+
+    #[test]
+    fn unreachable_goal_is_none() {
+        let grid = Grid::new(&["..#..", "..#..", "..#.."]);
+        assert_eq!(grid.astar((0, 0), (4, 2), Connectivity::Eight), None);
+        assert_eq!(grid.astar((0, 0), (9, 9), Connectivity::Eight), None);
+    }
+
+    #[test]
+    fn dijkstra_from_several_sources() {
+        let grid = Grid::new(&[".......", "###.###", "###.#.#"]);
+        let field = DijkstraMap::new(grid.width, grid.height, [(0, 0), (6, 0)], Connectivity::Four, grid.cost());
+        let row: Vec<Option<u32>> = (0..7).map(|col| field.get(col, 0)).collect();
+        assert_eq!(row, [0, 1, 2, 3, 2, 1, 0].map(Some));
+        assert_eq!(field.get(3, 2), Some(5));
+        assert_eq!(field.get(5, 2), None); // Fechado por muros
+        assert_eq!(field.get(9, 0), None); // Fora da grade
+
+        assert_eq!(field.path_from((4, 0)), Some(vec![(5, 0), (6, 0)]));
+        assert_eq!(field.path_from((3, 2)).unwrap().len(), 5);
+        assert_eq!(field.path_from((0, 0)), Some(vec![]));
+        assert_eq!(field.path_from((5, 2)), None);
+    }
+}
@@ -0,0 +1,103 @@
+use crate::combat::{Dice, Stats};
+use crate::equipment::Equipment;
+use crate::item::{Inventory, CARRY_CAPACITY};
+use crate::level::{Experience, Perk, LEVEL_HP};
+use crate::motion::Motion;
+use crate::status::{StatusKind, Statuses};
+use crate::turn::{Energy, NORMAL_SPEED};
+use crate::DEFAULT_FOV_RADIUS;
+
+// Jogador no começo do jogo
+const PLAYER_STATS: Stats = Stats::new(20, 3, 12, Dice::new(1, 6, 0));
+
+// --- Player ---
+pub struct Player {
+    // Tile onde o jogador está. É a verdade do jogo, o motion é só o desenho.
+    pub col: usize,
+    pub row: usize,
+    pub motion: Motion, // Posição animada em pixels
+
+    // Atributos base, sem equipamento nem efeitos. O que vale no jogo são os
+    // métodos effective_stats e fov, e a velocidade em energy (refresh_speed).
+    pub fov_radius: usize, // Até onde o jogador enxerga, em tiles
+    pub energy: Energy,    // Quando pode agir (ver turn.rs)
+    pub stats: Stats,      // Vida e combate (ver combat.rs)
+    pub inventory: Inventory,
+    pub equipment: Equipment, // Ver equipment.rs
+    pub status: Statuses,     // Efeitos com tempo (ver status.rs)
+    pub experience: Experience, // Nível e XP (ver level.rs)
+}
+
+impl Player {
+    // Jogador parado no tile (col, row)
+    pub fn new(col: usize, row: usize) -> Self {
+        Player {
+            col,
+            row,
+            motion: Motion::at(col, row),
+            fov_radius: DEFAULT_FOV_RADIUS,
+            energy: Energy::new(NORMAL_SPEED),
+            stats: PLAYER_STATS,
+            inventory: Inventory::new(CARRY_CAPACITY),
+            equipment: Equipment::default(),
+            status: Statuses::default(),
+            experience: Experience::default(),
+        }
+    }
+
+    // Teleporta pra (col, row), cancelando qualquer animação em andamento
+    pub fn place(&mut self, col: usize, row: usize) {
+        self.col = col;
+        self.row = row;
+        self.motion = Motion::at(col, row);
+    }
+
+    // Anda pra (col, row): a posição muda na hora, a animação vem atrás
+    pub fn step_to(&mut self, col: usize, row: usize) {
+        self.col = col;
+        self.row = row;
+        self.motion.slide_to(col, row);
+    }
+
+    pub fn tile(&self) -> (usize, usize) {
+        (self.col, self.row)
+    }
+
+    // Stats de combate com o equipamento (a vida é a mesma do base)
+    pub fn effective_stats(&self) -> Stats {
+        self.equipment.bonus().apply(&self.stats)
+    }
+
+    // Raio de visão com o equipamento, nunca menos de 1. Cego só vê os vizinhos.
+    pub fn fov(&self) -> usize {
+        if self.status.has(StatusKind::Blindness) {
+            return 1;
+        }
+        (self.fov_radius as i32 + self.equipment.bonus().fov).max(1) as usize
+    }
+
+    // Soma o XP. Cada nível que sobe aumenta a vida máxima (e cura o mesmo tanto).
+    // Devolve quantos níveis subiu.
+    pub fn gain_xp(&mut self, xp: u32) -> u32 {
+        let levels = self.experience.gain(xp);
+        let hp = LEVEL_HP * levels as i32;
+        self.stats.max_hp += hp;
+        self.stats.hp += hp;
+        levels
+    }
+
+    // Gasta um ponto de vantagem. false se não tem ponto.
+    pub fn choose_perk(&mut self, perk: Perk) -> bool {
+        if self.experience.perk_points == 0 {
+            return false;
+        }
+        self.experience.perk_points -= 1;
+        perk.apply(&mut self.stats, &mut self.fov_radius);
+        true
+    }
+
+    // Chamar depois de mexer no equipamento ou nos efeitos: a velocidade fica guardada na energia
+    pub fn refresh_speed(&mut self) {
+        self.energy.speed = (NORMAL_SPEED + self.equipment.bonus().speed + self.status.speed_bonus()).max(1);
+    }
+}
@@ -0,0 +1,62 @@
+// --- RNG ---
+// Gerador pseudoaleatório com seed explícita (SplitMix64).
+// O rand do macroquad é global, aqui a gente precisa que a mesma seed
+// sempre gere o mesmo resultado (mapas, etc.), então cada um tem o seu.
+#[derive(Debug, Clone)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Rng { state: seed }
+    }
+
+    // Estado atual, pra salvar. Rng::new(state) continua exatamente daqui.
+    pub fn state(&self) -> u64 {
+        self.state
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    // Inteiro em [low, high). Se high <= low, retorna low.
+    pub fn range(&mut self, low: usize, high: usize) -> usize {
+        if high <= low {
+            return low;
+        }
+        low + (self.next_u64() % (high - low) as u64) as usize
+    }
+
+    // Float em [0.0, 1.0)
+    pub fn next_f32(&mut self) -> f32 {
+        (self.next_u64() >> 40) as f32 / (1u64 << 24) as f32
+    }
+
+    pub fn chance(&mut self, probability: f32) -> bool {
+        self.next_f32() < probability
+    }
+
+    // Um dos itens, com chance proporcional ao peso de cada um.
+    // None se a lista está vazia ou todos os pesos são zero.
+    pub fn pick_weighted<'a, T>(&mut self, items: &'a [T], weight: impl Fn(&T) -> u32) -> Option<&'a T> {
+        let total: u64 = items.iter().map(|item| weight(item) as u64).sum();
+        if total == 0 {
+            return None;
+        }
+        let mut roll = self.next_u64() % total;
+        for item in items {
+            let w = weight(item) as u64;
+            if roll < w {
+                return Some(item);
+            }
+            roll -= w;
+        }
+        None
+    }
+}
@@ -0,0 +1,966 @@
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::combat::{Dice, Stats};
+use crate::dungeon::{Corpse, Dungeon, Floor};
+use crate::fov::TileMask;
+use crate::game::Run;
+use crate::item::{self, FloorItem, ItemKind, ItemStack};
+use crate::level::{self, Experience};
+use crate::map::Map;
+use crate::mapgen::LayoutTable;
+use crate::monster::{self, AiState, Monster};
+use crate::player::Player;
+use crate::rng::Rng;
+use crate::status::{StatusEffect, StatusKind, Statuses};
+use crate::tiles::TileRegistry;
+use crate::turn::Scheduler;
+use crate::DEFAULT_FOV_RADIUS;
+
+// --- SAVES ---
+// Cada slot é um arquivo texto na pasta de dados do usuário
+// (ex: ~/.local/share/aalg/saves/slot1.sav no Linux). O autosave fica
+// do lado (autosave.sav), no mesmo formato.
+// Cabeçalho com o estado do jogo, depois um bloco por andar já gerado:
+//
+//   version: 7
+//   saved_at: 1760790000
+//   slot: 1
+//   seed: 1760789123456
+//   max_depth: 6
+//   depth: 2
+//   time: 340
+//   rng: 12345678901234
+//   player: 10 5
+//   stats: 15 20 3 12 1d6      (hp max_hp ataque defesa dano)
+//   energy: 100
+//   fov: 8
+//   level: 3 52 1              (nível xp_total vantagens_pra_escolher)
+//   inventory: ! 3             (glifo quantidade, uma linha por pilha, na ordem do inventário)
+//   equipment: )               (glifo, um por item equipado; o slot vem do tipo)
+//   status: poison:3:1 haste:8:0   (efeito:turnos:potência, só se tiver algum)
+//   ---
+//   floor: 1
+//   name: Masmorra #1760789123456
+//   tileset: dng/map1.png
+//   width: 48
+//   height: 32
+//   monster: g 3 4 8 100 chase 5 6 slow:4:0   (glifo col row hp energia ia [col row] [efeitos])
+//   corpse: r 4 5
+//   item: $ 7 8 12             (glifo col row quantidade, o último do tile fica por cima)
+//   grid:
+//   (height linhas do mapa, iguais às do .map)
+//   explored:
+//   (height linhas, '#' = explorado, '.' = não)
+//
+// O que é visível agora não vai pro save, é recalculado ao carregar.
+pub const SLOT_COUNT: usize = 3;
+const APP_DIR: &str = "aalg";
+const AUTOSAVE_FILE: &str = "autosave.sav";
+
+// --- Versões ---
+// Mudou o formato? Sobe SAVE_VERSION e coloca no fim de MIGRATIONS uma função
+// que leva um save da versão anterior pra nova. Saves antigos passam por todas
+// as migrações em ordem antes de serem lidos, então read_run só conhece o formato atual.
+// Cada versão tem um save congelado em tests/fixtures/saves/ (ver os testes no fim).
+//
+//   1: primeiro formato, sem a linha "version" (todo save sem ela é versão 1)
+//   2: raio de visão do jogador (fov)
+//   3: slot do jogo (o autosave precisa saber onde o F5 salva)
+//   4: inventário e itens no chão
+//   5: equipamento
+//   6: efeitos de status (jogador e monstros)
+//   7: nível e XP do jogador
+pub const SAVE_VERSION: u32 = 7;
+
+// MIGRATIONS[i] leva da versão i + 1 pra i + 2, mexendo direto nas linhas do arquivo.
+type Migration = fn(&mut Vec<String>);
+const MIGRATIONS: [Migration; SAVE_VERSION as usize - 1] = [v1_add_fov, v2_add_slot, v3_add_items, v4_add_equipment, v5_add_status, v6_add_level];
+
+// --- Erros ---
+#[derive(Debug)]
+pub enum SaveError {
+    NoDataDir, // Sistema sem pasta de dados do usuário
+    Io { path: String, source: io::Error },
+    Syntax { line: usize, message: String },
+    MissingField(&'static str),
+    UnknownGlyph { line: usize, glyph: char },   // Tile que não está no tiles.cfg
+    UnknownMonster { line: usize, glyph: char }, // Monstro que não existe mais
+    UnknownItem { line: usize, glyph: char },    // Item que não existe mais
+    Invalid(&'static str),                       // Lê certo mas não faz sentido
+    UnsupportedVersion(u32),                     // Save de uma versão mais nova do jogo
+}
+
+impl fmt::Display for SaveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SaveError::NoDataDir => write!(f, "pasta de dados do usuário não encontrada"),
+            SaveError::Io { path, source } => write!(f, "não foi possível acessar '{}': {}", path, source),
+            SaveError::Syntax { line, message } => write!(f, "linha {}: {}", line, message),
+            SaveError::MissingField(field) => write!(f, "campo obrigatório ausente: '{}'", field),
+            SaveError::UnknownGlyph { line, glyph } => write!(f, "linha {}: tile desconhecido '{}'", line, glyph),
+            SaveError::UnknownMonster { line, glyph } => write!(f, "linha {}: monstro desconhecido '{}'", line, glyph),
+            SaveError::UnknownItem { line, glyph } => write!(f, "linha {}: item desconhecido '{}'", line, glyph),
+            SaveError::Invalid(message) => write!(f, "save inválido: {}", message),
+            SaveError::UnsupportedVersion(version) => {
+                write!(f, "save da versão {}, este jogo só lê até a versão {}", version, SAVE_VERSION)
+            }
+        }
+    }
+}
+
+impl std::error::Error for SaveError {}
+
+// --- Slots ---
+// Como um slot aparece na tela de Load
+pub enum SlotStatus {
+    Empty,
+    Ready { saved_at: u64, depth: u32, turn: u64, map_name: String },
+    Broken(String), // Save corrompido ou incompatível, com o motivo
+}
+
+pub struct SaveFile {
+    pub saved_at: u64, // Segundos desde a epoch (UTC)
+    pub run: Run,
+}
+
+pub fn save_dir() -> Result<PathBuf, SaveError> {
+    dirs::data_dir()
+        .map(|dir| dir.join(APP_DIR).join("saves"))
+        .ok_or(SaveError::NoDataDir)
+}
+
+// Slots contam de 1 a SLOT_COUNT
+fn slot_path(slot: usize) -> Result<PathBuf, SaveError> {
+    Ok(save_dir()?.join(format!("slot{}.sav", slot)))
+}
+
+pub fn save_slot(slot: usize, run: &Run) -> Result<(), SaveError> {
+    write_file(slot_path(slot)?, run)
+}
+
+// F5: salva no slot do jogo e devolve qual foi. O slot de um jogo novo só é
+// escolhido (pick_slot), não reservado, então dois jogos novos podem ter pego o
+// mesmo slot livre. Se o arquivo já é de outro jogo, escolhe outro slot agora.
+pub fn save_run(run: &mut Run, registry: &TileRegistry, layouts: &LayoutTable) -> Result<usize, SaveError> {
+    if !slot_is_ours(load_slot(run.slot, registry, layouts), run.dungeon.seed) {
+        run.slot = pick_slot(&scan_slots(registry, layouts));
+    }
+    save_slot(run.slot, run)?;
+    Ok(run.slot)
+}
+
+// O slot está livre ou tem um save desse mesmo jogo (mesma seed)? Quebrado conta como livre.
+fn slot_is_ours(saved: Result<Option<SaveFile>, SaveError>, seed: u64) -> bool {
+    !matches!(saved, Ok(Some(save)) if save.run.dungeon.seed != seed)
+}
+
+// Ok(None) = slot vazio
+pub fn load_slot(slot: usize, registry: &TileRegistry, layouts: &LayoutTable) -> Result<Option<SaveFile>, SaveError> {
+    let mut save = read_file(slot_path(slot)?, registry, layouts)?;
+    // O arquivo manda: um save copiado de outro slot passa a ser desse
+    if let Some(save) = &mut save {
+        save.run.slot = slot;
+    }
+    Ok(save)
+}
+
+// --- Autosave ---
+// Um só, sobrescrito ao trocar de andar e ao sair do jogo. Vira a opção "Continuar" do menu.
+fn autosave_path() -> Result<PathBuf, SaveError> {
+    Ok(save_dir()?.join(AUTOSAVE_FILE))
+}
+
+pub fn autosave(run: &Run) -> Result<(), SaveError> {
+    write_file(autosave_path()?, run)
+}
+
+pub fn load_autosave(registry: &TileRegistry, layouts: &LayoutTable) -> Result<Option<SaveFile>, SaveError> {
+    read_file(autosave_path()?, registry, layouts)
+}
+
+pub fn has_autosave() -> bool {
+    autosave_path().is_ok_and(|path| path.exists())
+}
+
+// Jogador morreu: não tem o que continuar
+pub fn delete_autosave() -> Result<(), SaveError> {
+    let path = autosave_path()?;
+    match fs::remove_file(&path) {
+        Err(e) if e.kind() != io::ErrorKind::NotFound => Err(SaveError::Io { path: path.display().to_string(), source: e }),
+        _ => Ok(()),
+    }
+}
+
+fn write_file(path: PathBuf, run: &Run) -> Result<(), SaveError> {
+    let io_error = |source| SaveError::Io { path: path.display().to_string(), source };
+
+    fs::create_dir_all(path.parent().expect("saves sempre ficam dentro de save_dir")).map_err(io_error)?;
+    // Escreve num temporário e troca, pra não estragar o save antigo se cair no meio
+    let temp = path.with_extension("tmp");
+    fs::write(&temp, write_run(run, now())).map_err(io_error)?;
+    fs::rename(&temp, &path).map_err(io_error)
+}
+
+// Ok(None) = arquivo não existe
+fn read_file(path: PathBuf, registry: &TileRegistry, layouts: &LayoutTable) -> Result<Option<SaveFile>, SaveError> {
+    let text = match fs::read_to_string(&path) {
+        Ok(text) => text,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+        Err(source) => return Err(SaveError::Io { path: path.display().to_string(), source }),
+    };
+    read_run(&text, registry, layouts).map(Some)
+}
+
+// Lê todos os slots (de verdade, pra já saber quais estão quebrados)
+pub fn scan_slots(registry: &TileRegistry, layouts: &LayoutTable) -> Vec<SlotStatus> {
+    (1..=SLOT_COUNT)
+        .map(|slot| match load_slot(slot, registry, layouts) {
+            Ok(None) => SlotStatus::Empty,
+            Ok(Some(save)) => SlotStatus::Ready {
+                saved_at: save.saved_at,
+                depth: save.run.dungeon.depth(),
+                turn: save.run.scheduler.turn(),
+                map_name: save.run.dungeon.floor().map.name.clone(),
+            },
+            Err(e) => SlotStatus::Broken(e.to_string()),
+        })
+        .collect()
+}
+
+// Slot pra um jogo novo: o primeiro vazio (ou quebrado), senão o save mais antigo.
+pub fn pick_slot(slots: &[SlotStatus]) -> usize {
+    if let Some(index) = slots.iter().position(|s| !matches!(s, SlotStatus::Ready { .. })) {
+        return index + 1;
+    }
+    slots
+        .iter()
+        .enumerate()
+        .min_by_key(|(_, s)| match s {
+            SlotStatus::Ready { saved_at, .. } => *saved_at,
+            _ => 0,
+        })
+        .map_or(1, |(index, _)| index + 1)
+}
+
+fn now() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs())
+}
+
+// "18/10/2026 14:03 UTC". Sem fuso horário local pra não precisar de crate de data.
+pub fn format_timestamp(secs: u64) -> String {
+    let days = (secs / 86_400) as i64;
+    let minutes = secs % 86_400 / 60;
+
+    // Dias desde 1970-01-01 -> data civil (algoritmo do Howard Hinnant)
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+
+    format!("{:02}/{:02}/{} {:02}:{:02} UTC", day, month, year, minutes / 60, minutes % 60)
+}
+
+// --- Escrita ---
+pub fn write_run(run: &Run, saved_at: u64) -> String {
+    let player = &run.player;
+    let stats = &player.stats;
+    let mut lines = vec![
+        "# save do aalg".to_owned(),
+        format!("version: {}", SAVE_VERSION),
+        format!("saved_at: {}", saved_at),
+        format!("slot: {}", run.slot),
+        format!("seed: {}", run.dungeon.seed),
+        format!("max_depth: {}", run.dungeon.max_depth()),
+        format!("depth: {}", run.dungeon.depth()),
+        format!("time: {}", run.scheduler.time()),
+        format!("rng: {}", run.rng.state()),
+        format!("player: {} {}", player.col, player.row),
+        format!("stats: {} {} {} {} {}", stats.hp, stats.max_hp, stats.attack, stats.defense, stats.damage),
+        format!("energy: {}", player.energy.value),
+        format!("fov: {}", player.fov_radius),
+        format!("level: {} {} {}", player.experience.level, player.experience.xp, player.experience.perk_points),
+    ];
+    for stack in player.inventory.stacks() {
+        lines.push(format!("inventory: {} {}", stack.kind.glyph, stack.count));
+    }
+    for stack in player.equipment.worn() {
+        lines.push(format!("equipment: {}", stack.kind.glyph));
+    }
+    if !player.status.effects().is_empty() {
+        lines.push(format!("status: {}", write_statuses(&player.status)));
+    }
+
+    for floor in run.dungeon.floors() {
+        let map = &floor.map;
+        lines.push("---".to_owned());
+        lines.push(format!("floor: {}", floor.depth));
+        lines.push(format!("name: {}", map.name));
+        lines.push(format!("tileset: {}", map.tileset));
+        lines.push(format!("width: {}", map.width));
+        lines.push(format!("height: {}", map.height));
+        for m in &floor.monsters {
+            let ai = match m.ai {
+                AiState::Wander => "wander".to_owned(),
+                AiState::Chase { last_seen: (col, row) } => format!("chase {} {}", col, row),
+            };
+            let mut line = format!("monster: {} {} {} {} {} {}", m.kind.glyph, m.col, m.row, m.stats.hp, m.energy.value, ai);
+            if !m.status.effects().is_empty() {
+                line = format!("{} {}", line, write_statuses(&m.status));
+            }
+            lines.push(line);
+        }
+        for c in &floor.corpses {
+            lines.push(format!("corpse: {} {} {}", c.kind.glyph, c.col, c.row));
+        }
+        for i in &floor.items {
+            lines.push(format!("item: {} {} {} {}", i.stack.kind.glyph, i.col, i.row, i.stack.count));
+        }
+        lines.push("grid:".to_owned());
+        for row in 0..map.height {
+            lines.push((0..map.width).map(|col| map.tile(col as isize, row as isize)).collect());
+        }
+        lines.push("explored:".to_owned());
+        for row in 0..map.height {
+            lines.push((0..map.width).map(|col| if floor.explored.get(col, row) { '#' } else { '.' }).collect());
+        }
+    }
+
+    lines.push(String::new()); // Termina com \n
+    lines.join("\n")
+}
+
+// "poison:3:1 haste:8:0"
+fn write_statuses(statuses: &Statuses) -> String {
+    statuses
+        .effects()
+        .iter()
+        .map(|e| format!("{}:{}:{}", e.kind.name(), e.turns, e.potency))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+// --- Leitura ---
+// Linhas numeradas a partir de 1, pras mensagens de erro
+struct Reader<'a> {
+    lines: std::iter::Peekable<std::iter::Enumerate<std::str::Lines<'a>>>,
+}
+
+impl<'a> Reader<'a> {
+    fn new(text: &'a str) -> Self {
+        Reader { lines: text.lines().enumerate().peekable() }
+    }
+
+    // Próxima linha crua (grades podem começar com espaço, não dá pra dar trim)
+    fn next_raw(&mut self) -> Option<(usize, &'a str)> {
+        self.lines.next().map(|(idx, line)| (idx + 1, line))
+    }
+
+    // Lê "chave: valor" até a linha end (ou o fim do arquivo). Pula vazias e comentários.
+    fn fields(&mut self, end: &str) -> Result<Fields<'a>, SaveError> {
+        let mut fields = Vec::new();
+        while let Some((line_no, line)) = self.next_raw() {
+            let line = line.trim();
+            if line == end {
+                return Ok(Fields(fields));
+            }
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (key, value) = line
+                .split_once(':')
+                .ok_or_else(|| syntax(line_no, &format!("esperava 'chave: valor', achei '{}'", line)))?;
+            fields.push((line_no, key.trim(), value.trim()));
+        }
+        Ok(Fields(fields))
+    }
+
+    // height linhas seguidas com exatamente width caracteres
+    fn grid(&mut self, width: usize, height: usize) -> Result<Vec<(usize, Vec<char>)>, SaveError> {
+        (0..height)
+            .map(|_| {
+                let (line_no, line) = self.next_raw().ok_or(SaveError::Invalid("arquivo terminou no meio de uma grade"))?;
+                let row: Vec<char> = line.chars().collect();
+                if row.len() != width {
+                    return Err(syntax(line_no, &format!("esperava {} colunas, achei {}", width, row.len())));
+                }
+                Ok((line_no, row))
+            })
+            .collect()
+    }
+
+    fn expect(&mut self, expected: &str) -> Result<(), SaveError> {
+        match self.next_raw() {
+            Some((_, line)) if line.trim() == expected => Ok(()),
+            Some((line_no, line)) => Err(syntax(line_no, &format!("esperava '{}', achei '{}'", expected, line))),
+            None => Err(SaveError::Invalid("arquivo terminou antes da hora")),
+        }
+    }
+
+    fn at_end(&mut self) -> bool {
+        self.lines.peek().is_none()
+    }
+}
+
+struct Fields<'a>(Vec<(usize, &'a str, &'a str)>);
+
+impl<'a> Fields<'a> {
+    fn raw(&self, key: &'static str) -> Result<(usize, &'a str), SaveError> {
+        self.0
+            .iter()
+            .find(|(_, k, _)| *k == key)
+            .map(|&(line, _, value)| (line, value))
+            .ok_or(SaveError::MissingField(key))
+    }
+
+    fn get<T: FromStr>(&self, key: &'static str) -> Result<T, SaveError> {
+        let (line, value) = self.raw(key)?;
+        value.parse().map_err(|_| syntax(line, &format!("valor inválido pra '{}': '{}'", key, value)))
+    }
+
+    // Todas as ocorrências de uma chave que pode repetir (monster, corpse, item)
+    fn all(&self, key: &'static str) -> impl Iterator<Item = (usize, &'a str)> + '_ {
+        self.0.iter().filter(move |(_, k, _)| *k == key).map(|&(line, _, value)| (line, value))
+    }
+}
+
+// layouts é a masmorra configurada agora: um save mais fundo que ela não
+// teria como gerar os andares que faltam.
+pub fn read_run(text: &str, registry: &TileRegistry, layouts: &LayoutTable) -> Result<SaveFile, SaveError> {
+    let text = migrate(text)?;
+    let mut reader = Reader::new(&text);
+    let header = reader.fields("---")?;
+
+    let mut floors = Vec::new();
+    while !reader.at_end() {
+        floors.push(read_floor(&mut reader, registry)?);
+        if !reader.at_end() {
+            reader.expect("---")?;
+        }
+    }
+
+    let depth: u32 = header.get("depth")?;
+    let max_depth: u32 = header.get("max_depth")?;
+    if depth == 0 || depth as usize > floors.len() || floors.len() > max_depth as usize {
+        return Err(SaveError::Invalid("andar atual fora da masmorra"));
+    }
+    if max_depth > layouts.max_depth() {
+        return Err(SaveError::Invalid("masmorra mais funda que a do dungeon.cfg"));
+    }
+    for (index, floor) in floors.iter().enumerate() {
+        if floor.depth as usize != index + 1 {
+            return Err(SaveError::Invalid("andares fora de ordem"));
+        }
+    }
+
+    let (line, position) = header.raw("player")?;
+    let (col, row) = match position.split_whitespace().collect::<Vec<_>>()[..] {
+        [col, row] => parse_position(col, row),
+        _ => None,
+    }
+    .ok_or_else(|| syntax(line, "esperava 'player: col row'"))?;
+    let map = &floors[depth as usize - 1].map;
+    if col >= map.width || row >= map.height {
+        return Err(SaveError::Invalid("jogador fora do mapa"));
+    }
+
+    let mut player = Player::new(col, row);
+    player.stats = parse_stats(header.raw("stats")?)?;
+    player.energy.value = header.get("energy")?;
+    player.fov_radius = header.get("fov")?;
+    player.experience = parse_experience(header.raw("level")?)?;
+    for (line, value) in header.all("inventory") {
+        let parts: Vec<&str> = value.split_whitespace().collect();
+        let [glyph, count] = parts[..] else {
+            return Err(syntax(line, "esperava 'inventory: glifo quantidade'"));
+        };
+        let stack = parse_stack(line, glyph, count)?;
+        player.inventory.add(stack).map_err(|_| SaveError::Invalid("inventário não cabe no jogador"))?;
+    }
+    for (line, glyph) in header.all("equipment") {
+        let stack = parse_stack(line, glyph, "1")?;
+        if stack.kind.equip.is_none() {
+            return Err(syntax(line, &format!("{} não é equipamento", stack.kind.name)));
+        }
+        if player.equipment.equip(stack).is_some() {
+            return Err(SaveError::Invalid("dois equipamentos no mesmo slot"));
+        }
+    }
+    if let Ok((line, value)) = header.raw("status") {
+        for token in value.split_whitespace() {
+            player.status.apply(parse_status(line, token)?);
+        }
+    }
+    player.refresh_speed(); // Velocidade não vai pro save, sai do equipamento e dos efeitos
+
+    let dungeon = Dungeon::restore(header.get("seed")?, floors, depth, max_depth);
+    let slot: usize = header.get("slot")?;
+    if slot == 0 || slot > SLOT_COUNT {
+        return Err(SaveError::Invalid("slot fora da faixa"));
+    }
+    let mut run = Run::new(dungeon, player, slot);
+    run.scheduler = Scheduler::at(header.get("time")?);
+    run.rng = Rng::new(header.get("rng")?);
+
+    Ok(SaveFile { saved_at: header.get("saved_at")?, run })
+}
+
+// Traz o texto de um save de qualquer versão conhecida pra SAVE_VERSION.
+fn migrate(text: &str) -> Result<String, SaveError> {
+    let mut lines: Vec<String> = text.lines().map(str::to_owned).collect();
+
+    // A versão fica no cabeçalho (antes do primeiro ---). Sem ela é versão 1.
+    let header_end = lines.iter().position(|l| l.trim() == "---").unwrap_or(lines.len());
+    let version_line = lines[..header_end]
+        .iter()
+        .position(|l| l.split_once(':').is_some_and(|(key, _)| key.trim() == "version"));
+    let version = match version_line {
+        Some(i) => {
+            let value = lines[i].split_once(':').expect("achou ':' acima").1.trim();
+            value.parse().map_err(|_| syntax(i + 1, &format!("versão inválida: '{}'", value)))?
+        }
+        None => 1,
+    };
+    if version == 0 || version > SAVE_VERSION {
+        return Err(SaveError::UnsupportedVersion(version));
+    }
+
+    for migration in &MIGRATIONS[version as usize - 1..] {
+        migration(&mut lines);
+    }
+
+    // Carimba a versão atual (as migrações não precisam se preocupar com isso)
+    let stamp = format!("version: {}", SAVE_VERSION);
+    match version_line {
+        Some(i) => lines[i] = stamp,
+        None => lines.insert(0, stamp),
+    }
+    lines.push(String::new());
+    Ok(lines.join("\n"))
+}
+
+// Põe uma linha no fim do cabeçalho, logo antes do primeiro ---
+fn insert_header_line(lines: &mut Vec<String>, line: String) {
+    let header_end = lines.iter().position(|l| l.trim() == "---").unwrap_or(lines.len());
+    lines.insert(header_end, line);
+}
+
+// 1 -> 2: o raio de visão passou a ser salvo, saves antigos ficam com o padrão
+fn v1_add_fov(lines: &mut Vec<String>) {
+    insert_header_line(lines, format!("fov: {}", DEFAULT_FOV_RADIUS));
+}
+
+// 2 -> 3: saves antigos só existiam em slots, e load_slot corrige pelo nome do arquivo
+fn v2_add_slot(lines: &mut Vec<String>) {
+    insert_header_line(lines, "slot: 1".to_owned());
+}
+
+// 3 -> 4: antes não tinha item nenhum, então inventário vazio e chão limpo
+// (linhas ausentes) já é o certo. Fica aqui só pra versão andar.
+fn v3_add_items(_lines: &mut Vec<String>) {}
+
+// 4 -> 5: mesma coisa, save antigo não tem nada equipado
+fn v4_add_equipment(_lines: &mut Vec<String>) {}
+
+// 5 -> 6: ninguém tinha efeito nenhum
+fn v5_add_status(_lines: &mut Vec<String>) {}
+
+// 6 -> 7: começa no nível 1 sem XP (o que já matou não conta)
+fn v6_add_level(lines: &mut Vec<String>) {
+    insert_header_line(lines, "level: 1 0 0".to_owned());
+}
+
+fn read_floor(reader: &mut Reader, registry: &TileRegistry) -> Result<Floor, SaveError> {
+    let fields = reader.fields("grid:")?;
+    let width: usize = fields.get("width")?;
+    let height: usize = fields.get("height")?;
+    if width == 0 || height == 0 {
+        return Err(SaveError::Invalid("andar com tamanho zero"));
+    }
+
+    let mut tiles = Vec::with_capacity(width * height);
+    for (line, row) in reader.grid(width, height)? {
+        if let Some(&glyph) = row.iter().find(|&&glyph| !registry.contains(glyph)) {
+            return Err(SaveError::UnknownGlyph { line, glyph });
+        }
+        tiles.extend(row);
+    }
+    let map = Map::from_grid(fields.get("name")?, fields.get("tileset")?, width, height, tiles);
+
+    reader.expect("explored:")?;
+    let mut explored = TileMask::new(width, height);
+    for (row_idx, (line, row)) in reader.grid(width, height)?.into_iter().enumerate() {
+        for (col_idx, mark) in row.into_iter().enumerate() {
+            match mark {
+                '#' => explored.set(col_idx, row_idx),
+                '.' => {}
+                _ => return Err(syntax(line, "explorado deve ter só '#' e '.'")),
+            }
+        }
+    }
+
+    let in_map = |col: usize, row: usize| col < width && row < height;
+    let mut monsters = Vec::new();
+    for (line, value) in fields.all("monster") {
+        let monster = parse_monster(line, value)?;
+        if !in_map(monster.col, monster.row) {
+            return Err(SaveError::Invalid("monstro fora do mapa"));
+        }
+        monsters.push(monster);
+    }
+    let mut corpses = Vec::new();
+    for (line, value) in fields.all("corpse") {
+        let parts: Vec<&str> = value.split_whitespace().collect();
+        let [glyph, col, row] = parts[..] else {
+            return Err(syntax(line, "esperava 'corpse: glifo col row'"));
+        };
+        let kind = parse_kind(line, glyph)?;
+        let (col, row) = parse_position(col, row).ok_or_else(|| syntax(line, "posição inválida"))?;
+        if !in_map(col, row) {
+            return Err(SaveError::Invalid("cadáver fora do mapa"));
+        }
+        corpses.push(Corpse { col, row, kind });
+    }
+    let mut items = Vec::new();
+    for (line, value) in fields.all("item") {
+        let parts: Vec<&str> = value.split_whitespace().collect();
+        let [glyph, col, row, count] = parts[..] else {
+            return Err(syntax(line, "esperava 'item: glifo col row quantidade'"));
+        };
+        let stack = parse_stack(line, glyph, count)?;
+        let (col, row) = parse_position(col, row).ok_or_else(|| syntax(line, "posição inválida"))?;
+        if !in_map(col, row) {
+            return Err(SaveError::Invalid("item fora do mapa"));
+        }
+        items.push(FloorItem { col, row, stack });
+    }
+
+    Ok(Floor {
+        depth: fields.get("floor")?,
+        visible: TileMask::new(width, height),
+        explored,
+        map,
+        monsters,
+        corpses,
+        items,
+    })
+}
+
+fn parse_monster(line: usize, value: &str) -> Result<Monster, SaveError> {
+    let parts: Vec<&str> = value.split_whitespace().collect();
+    let bad = || syntax(line, "esperava 'monster: glifo col row hp energia wander|chase col row [efeitos]'");
+    if parts.len() < 6 {
+        return Err(bad());
+    }
+
+    let kind = parse_kind(line, parts[0])?;
+    let (col, row) = parse_position(parts[1], parts[2]).ok_or_else(bad)?;
+    let number = |s: &str| s.parse::<i32>().map_err(|_| bad());
+
+    let mut monster = Monster::new(kind, col, row);
+    monster.stats.hp = number(parts[3])?;
+    monster.energy.value = number(parts[4])?;
+    let statuses = match parts[5..] {
+        ["wander", ref rest @ ..] => {
+            monster.ai = AiState::Wander;
+            rest
+        }
+        ["chase", col, row, ref rest @ ..] => {
+            monster.ai = AiState::Chase {
+                last_seen: parse_position(col, row).ok_or_else(bad)?,
+            };
+            rest
+        }
+        _ => return Err(bad()),
+    };
+    for token in statuses {
+        monster.status.apply(parse_status(line, token)?);
+    }
+    monster.refresh_speed();
+    Ok(monster)
+}
+
+fn parse_glyph(line: usize, glyph: &str) -> Result<char, SaveError> {
+    let mut chars = glyph.chars();
+    match (chars.next(), chars.next()) {
+        (Some(c), None) => Ok(c),
+        _ => Err(syntax(line, &format!("glifo deve ser um único caractere: '{}'", glyph))),
+    }
+}
+
+fn parse_kind(line: usize, glyph: &str) -> Result<&'static monster::MonsterKind, SaveError> {
+    let glyph = parse_glyph(line, glyph)?;
+    monster::kind_for_glyph(glyph).ok_or(SaveError::UnknownMonster { line, glyph })
+}
+
+// Pilha de item: não empilhável só pode ter 1, e nenhuma fica vazia
+fn parse_stack(line: usize, glyph: &str, count: &str) -> Result<ItemStack, SaveError> {
+    let glyph = parse_glyph(line, glyph)?;
+    let kind: &'static ItemKind = item::kind_for_glyph(glyph).ok_or(SaveError::UnknownItem { line, glyph })?;
+    let count: u32 = count.parse().map_err(|_| syntax(line, &format!("quantidade inválida: '{}'", count)))?;
+    if count == 0 || (!kind.stackable && count > 1) {
+        return Err(syntax(line, &format!("quantidade impossível pra {}: {}", kind.name, count)));
+    }
+    Ok(ItemStack::new(kind, count))
+}
+
+// "poison:3:1" (efeito:turnos:potência)
+fn parse_status(line: usize, token: &str) -> Result<StatusEffect, SaveError> {
+    let bad = || syntax(line, &format!("efeito inválido: '{}'", token));
+    let parts: Vec<&str> = token.split(':').collect();
+    let [name, turns, potency] = parts[..] else {
+        return Err(bad());
+    };
+    let kind = StatusKind::parse(name).ok_or_else(bad)?;
+    let turns: u32 = turns.parse().map_err(|_| bad())?;
+    if turns == 0 {
+        return Err(bad());
+    }
+    Ok(StatusEffect::new(kind, turns, potency.parse().map_err(|_| bad())?))
+}
+
+fn parse_stats((line, value): (usize, &str)) -> Result<Stats, SaveError> {
+    let bad = || syntax(line, "esperava 'stats: hp max_hp ataque defesa dano'");
+    let parts: Vec<&str> = value.split_whitespace().collect();
+    let [hp, max_hp, attack, defense, damage] = parts[..] else {
+        return Err(bad());
+    };
+    let number = |s: &str| s.parse::<i32>().map_err(|_| bad());
+    let mut stats = Stats::new(number(max_hp)?, number(attack)?, number(defense)?, Dice::parse(damage).ok_or_else(bad)?);
+    stats.hp = number(hp)?;
+    Ok(stats)
+}
+
+// O XP tem que estar na faixa do nível: o jogo nunca deixa um sem o outro
+fn parse_experience((line, value): (usize, &str)) -> Result<Experience, SaveError> {
+    let bad = || syntax(line, "esperava 'level: nível xp vantagens'");
+    let parts: Vec<&str> = value.split_whitespace().collect();
+    let [level, xp, perk_points] = parts[..] else {
+        return Err(bad());
+    };
+    let number = |s: &str| s.parse::<u32>().map_err(|_| bad());
+    let experience = Experience { level: number(level)?, xp: number(xp)?, perk_points: number(perk_points)? };
+    let floor = experience.level.checked_sub(1).map(level::next_level_xp);
+    if floor.is_none_or(|floor| experience.xp < floor) || experience.xp >= experience.next() {
+        return Err(SaveError::Invalid("nível não bate com o XP"));
+    }
+    Ok(experience)
+}
+
+fn parse_position(col: &str, row: &str) -> Option<(usize, usize)> {
+    Some((col.parse().ok()?, row.parse().ok()?))
+}
+
+fn syntax(line: usize, message: &str) -> SaveError {
+    SaveError::Syntax { line, message: message.to_owned() }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::content;
+    use crate::mapgen::{SPAWN, STAIRS_UP};
+    use crate::turn::{Command, NORMAL_SPEED};
+    use std::pin::pin;
+    use std::task::{Context, Poll, Waker};
+
+    const SEED: u64 = 1_760_789_123_456;
+    const SAVED_AT: u64 = 1_760_790_000;
+
+    // Saves congelados de cada versão do formato (tests/fixtures/saves). Cada um
+    // foi escrito pelo write_run da sua versão a partir do sample_run da época,
+    // então não se edita à mão. Mudou o formato? Sobe a versão e gera o arquivo
+    // novo com `cargo test -- --ignored write_current_fixture`.
+    const FIXTURES: [(u32, &str); SAVE_VERSION as usize] = [
+        (1, include_str!("../tests/fixtures/saves/v1.sav")),
+        (2, include_str!("../tests/fixtures/saves/v2.sav")),
+        (3, include_str!("../tests/fixtures/saves/v3.sav")),
+        (4, include_str!("../tests/fixtures/saves/v4.sav")),
+        (5, include_str!("../tests/fixtures/saves/v5.sav")),
+        (6, include_str!("../tests/fixtures/saves/v6.sav")),
+        (7, include_str!("../tests/fixtures/saves/v7.sav")),
+    ];
+
+    fn registry() -> TileRegistry {
+        TileRegistry::parse(&fs::read_to_string("assets/tiles.cfg").unwrap()).unwrap()
+    }
+
+    fn layouts() -> LayoutTable {
+        LayoutTable::parse(&fs::read_to_string("assets/dungeon.cfg").unwrap()).unwrap()
+    }
+
+    // Os geradores não esperam nada de verdade (só mapa de arquivo carrega
+    // com macroquad), então dá pra rodar o async na mão.
+    fn ready<T>(future: impl Future<Output = T>) -> T {
+        match pin!(future).poll(&mut Context::from_waker(Waker::noop())) {
+            Poll::Ready(value) => value,
+            Poll::Pending => panic!("o dungeon.cfg dos testes não pode ter layout de arquivo"),
+        }
+    }
+
+    // Um jogo de verdade, sempre igual: alguns turnos no primeiro andar,
+    // desce pro segundo e espera mais uns turnos lá. Os campos que o jogo ainda
+    // não mexe ficam diferentes do padrão, pra ver que vieram do arquivo.
+    fn sample_run() -> Run {
+        content::tests::load_shipped();
+        let (registry, layouts) = (registry(), layouts());
+        let dungeon = ready(Dungeon::new(SEED, &layouts, &registry)).unwrap();
+        let mut run = Run::new(dungeon, Player::new(0, 0), 2);
+        run.player.fov_radius = 7;
+        for (glyph, count) in [('!', 3), (')', 1)] {
+            let stack = ItemStack::new(item::kind_for_glyph(glyph).unwrap(), count);
+            run.player.inventory.add(stack).unwrap();
+        }
+        for glyph in [')', '"'] {
+            run.player.equipment.equip(ItemStack::new(item::kind_for_glyph(glyph).unwrap(), 1));
+        }
+        run.player.status.apply(StatusEffect::new(StatusKind::Regeneration, 40, 1));
+        run.player.status.apply(StatusEffect::new(StatusKind::Blindness, 40, 0));
+        run.player.refresh_speed();
+        run.player.experience.gain(50);
+
+        let (col, row) = run.dungeon.floor().map.find(SPAWN).unwrap();
+        run.player.place(col, row);
+        for _ in 0..5 {
+            run.perform(Command::Wait, &registry);
+        }
+
+        ready(run.dungeon.go_to(2, &layouts, &registry)).unwrap();
+        let (col, row) = run.dungeon.floor().map.find(STAIRS_UP).unwrap();
+        run.player.place(col, row);
+        let monster = &mut run.dungeon.floor_mut().monsters[0];
+        monster.status.apply(StatusEffect::new(StatusKind::Slow, 40, 0));
+        monster.refresh_speed();
+        for _ in 0..5 {
+            run.perform(Command::Wait, &registry);
+        }
+        assert!(!run.is_over());
+        run
+    }
+
+    fn load(version: u32, text: &str) -> SaveFile {
+        content::tests::load_shipped();
+        read_run(text, &registry(), &layouts()).unwrap_or_else(|e| panic!("v{}: {}", version, e))
+    }
+
+    #[test]
+    fn every_old_version_migrates_to_the_current_one() {
+        for (version, text) in FIXTURES {
+            let migrated = migrate(text).unwrap();
+            let header = migrated.split("---").next().unwrap();
+            assert_eq!(header.matches("version:").count(), 1, "v{}", version);
+            assert!(header.contains(&format!("version: {}\n", SAVE_VERSION)), "v{}", version);
+        }
+    }
+
+    #[test]
+    fn every_old_version_loads() {
+        for (version, text) in FIXTURES {
+            let save = load(version, text);
+            let run = &save.run;
+            let at_least = |since: u32| version >= since;
+
+            // O que existe desde a versão 1
+            assert_eq!((save.saved_at, run.dungeon.seed), (SAVED_AT, SEED), "v{}", version);
+            assert_eq!((run.dungeon.depth(), run.dungeon.max_depth(), run.dungeon.floors().len()), (2, 6, 2));
+            assert_eq!(run.dungeon.floor().map.find(STAIRS_UP), Some(run.player.tile()), "v{}", version);
+            assert!(run.scheduler.time() > 0 && !run.is_over(), "v{}", version);
+            assert!(run.dungeon.floors().iter().all(|floor| !floor.monsters.is_empty()), "v{}", version);
+
+            // O que cada versão acrescentou (ou o padrão que a migração pôs)
+            let fov = if at_least(2) { 7 } else { DEFAULT_FOV_RADIUS };
+            assert_eq!(run.player.fov_radius, fov, "v{}", version);
+            assert_eq!(run.slot, if at_least(3) { 2 } else { 1 }, "v{}", version);
+            let inventory: Vec<String> = run.player.inventory.stacks().iter().map(ItemStack::label).collect();
+            let expected: &[&str] = if at_least(4) { &["Poção de cura x3", "Adaga"] } else { &[] };
+            assert_eq!(inventory, expected, "v{}", version);
+            let floor_items = run.dungeon.floors().iter().all(|floor| !floor.items.is_empty());
+            let no_items = run.dungeon.floors().iter().all(|floor| floor.items.is_empty());
+            assert!(if at_least(4) { floor_items } else { no_items }, "v{}", version);
+            let worn: String = run.player.equipment.worn().map(|stack| stack.kind.glyph).collect();
+            assert_eq!(worn, if at_least(5) { ")\"" } else { "" }, "v{}", version);
+            let speed = if at_least(5) { NORMAL_SPEED + 5 } else { NORMAL_SPEED };
+            assert_eq!(run.player.energy.speed, speed, "v{}", version);
+            let player_status: Vec<StatusKind> = run.player.status.effects().iter().map(|e| e.kind).collect();
+            let expected: &[StatusKind] = if at_least(6) { &[StatusKind::Regeneration, StatusKind::Blindness] } else { &[] };
+            assert_eq!(player_status, expected, "v{}", version);
+            let slowed = run.dungeon.floor().monsters.iter().filter(|m| m.status.has(StatusKind::Slow)).count();
+            assert_eq!(slowed, if at_least(6) { 1 } else { 0 }, "v{}", version);
+            let experience = if at_least(7) { Experience { level: 3, xp: 50, perk_points: 2 } } else { Experience::default() };
+            assert_eq!(run.player.experience, experience, "v{}", version);
+        }
+    }
+
+    #[test]
+    fn current_version_writes_back_the_same_file() {
+        let (version, text) = FIXTURES[SAVE_VERSION as usize - 1];
+        let save = load(version, text);
+        assert_eq!(write_run(&save.run, save.saved_at), text);
+    }
+
+    #[test]
+    fn newer_version_is_rejected() {
+        let (_, text) = FIXTURES[SAVE_VERSION as usize - 1];
+        let newer = SAVE_VERSION + 1;
+        let text = text.replace(&format!("version: {}", SAVE_VERSION), &format!("version: {}", newer));
+        let error = read_run(&text, &registry(), &layouts()).err().unwrap();
+        assert!(matches!(error, SaveError::UnsupportedVersion(v) if v == newer), "{}", error);
+    }
+
+    // Gera o save congelado da versão atual. Só roda pedindo (--ignored), quando
+    // o formato muda: os das versões antigas nunca são regravados.
+    #[test]
+    #[ignore]
+    fn write_current_fixture() {
+        let path = format!("tests/fixtures/saves/v{}.sav", SAVE_VERSION);
+        fs::write(path, write_run(&sample_run(), SAVED_AT)).unwrap();
+    }
+
+    #[test]
+    fn new_game_writes_back_the_same_file() {
+        let text = write_run(&sample_run(), SAVED_AT);
+        let save = read_run(&text, &registry(), &layouts()).unwrap();
+        assert_eq!(save.saved_at, SAVED_AT);
+        assert_eq!((save.run.dungeon.depth(), save.run.dungeon.floors().len()), (2, 2));
+        assert_eq!(write_run(&save.run, SAVED_AT), text);
+    }
+
+    #[test]
+    fn slot_taken_by_another_game() {
+        let load = || read_run(&write_run(&sample_run(), SAVED_AT), &registry(), &layouts());
+        assert!(slot_is_ours(Ok(None), SEED));
+        assert!(slot_is_ours(Ok(Some(load().unwrap())), SEED));
+        assert!(!slot_is_ours(Ok(Some(load().unwrap())), SEED + 1));
+        assert!(slot_is_ours(Err(SaveError::Invalid("quebrado")), SEED));
+    }
+
+    #[test]
+    fn deeper_than_dungeon_cfg_is_rejected() {
+        let text = write_run(&sample_run(), SAVED_AT);
+        let shallow = LayoutTable::parse("1-2 bsp").unwrap();
+        let error = read_run(&text, &registry(), &shallow).err().unwrap();
+        assert!(matches!(error, SaveError::Invalid(_)), "{}", error);
+    }
+
+    #[test]
+    fn experience_must_match_the_level() {
+        let parse = |value: &str| parse_experience((3, value));
+        assert_eq!(parse("1 0 0").unwrap(), Experience::default());
+        assert_eq!(parse("3 89 2").unwrap(), Experience { level: 3, xp: 89, perk_points: 2 });
+        for bad in ["3 44 0", "3 90 0", "0 0 0"] {
+            assert!(matches!(parse(bad), Err(SaveError::Invalid(_))), "passou: {}", bad);
+        }
+        for bad in ["1 0", "1 0 0 0", "1 -3 0", "um 0 0"] {
+            assert!(matches!(parse(bad), Err(SaveError::Syntax { line: 3, .. })), "passou: {}", bad);
+        }
+    }
+}
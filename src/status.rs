@@ -0,0 +1,259 @@
+use macroquad::prelude::*;
+use serde::Deserialize;
+
+use crate::turn::NORMAL_SPEED;
+
+// --- EFEITOS ---
+// Efeitos com tempo (em turnos normais) que qualquer ator pode ter: jogador
+// e monstros guardam um Statuses cada. O relógio chama tick uma vez por turno
+// (ver Scheduler::advance); quem usa o efeito só pergunta has/speed_bonus:
+//   regeneração / veneno  vida a cada turno (potency por turno)
+//   pressa / lentidão     velocidade (ver speed_bonus)
+//   cegueira              visão do jogador cai pra 1 tile, monstro cego só nota o jogador colado
+//   confusão              às vezes anda pra um lado qualquer (CONFUSION_CHANCE)
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+pub enum StatusKind {
+    Regeneration,
+    Poison,
+    Haste,
+    Slow,
+    Blindness,
+    Confusion,
+}
+
+// O que acontece quando o ator já tem o efeito e ganha de novo
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Stacking {
+    Refresh,   // Fica a duração maior, a potência maior
+    Extend,    // Soma as durações
+    Intensify, // Soma as potências, fica a duração maior
+}
+
+// Chance de um passo de quem está confuso sair pra um lado qualquer
+pub const CONFUSION_CHANCE: f32 = 0.5;
+const HASTE_BONUS: i32 = NORMAL_SPEED; // Dobra a velocidade normal
+const SLOW_PENALTY: i32 = NORMAL_SPEED / 2; // Metade da velocidade normal
+// Ícones do HUD: quadros ICON_SIZE x ICON_SIZE no status.png, na ordem de StatusKind::ALL
+pub const ICON_SIZE: f32 = 32.0;
+
+impl StatusKind {
+    pub const ALL: [StatusKind; 6] = [
+        StatusKind::Regeneration,
+        StatusKind::Poison,
+        StatusKind::Haste,
+        StatusKind::Slow,
+        StatusKind::Blindness,
+        StatusKind::Confusion,
+    ];
+
+    // Nome no save
+    pub fn name(self) -> &'static str {
+        match self {
+            StatusKind::Regeneration => "regeneration",
+            StatusKind::Poison => "poison",
+            StatusKind::Haste => "haste",
+            StatusKind::Slow => "slow",
+            StatusKind::Blindness => "blindness",
+            StatusKind::Confusion => "confusion",
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            StatusKind::Regeneration => "Regeneração",
+            StatusKind::Poison => "Veneno",
+            StatusKind::Haste => "Pressa",
+            StatusKind::Slow => "Lentidão",
+            StatusKind::Blindness => "Cegueira",
+            StatusKind::Confusion => "Confusão",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        StatusKind::ALL.into_iter().find(|kind| kind.name() == s)
+    }
+
+    pub fn stacking(self) -> Stacking {
+        match self {
+            StatusKind::Poison => Stacking::Intensify, // Cada mordida a mais arde mais
+            StatusKind::Confusion => Stacking::Extend,
+            StatusKind::Regeneration | StatusKind::Haste | StatusKind::Slow | StatusKind::Blindness => Stacking::Refresh,
+        }
+    }
+
+    // Efeitos que fazem alguma coisa com a potency (vida por turno)
+    pub fn uses_potency(self) -> bool {
+        matches!(self, StatusKind::Regeneration | StatusKind::Poison)
+    }
+
+    // Efeito que some quando esse chega (pressa e lentidão se anulam)
+    fn opposite(self) -> Option<StatusKind> {
+        match self {
+            StatusKind::Haste => Some(StatusKind::Slow),
+            StatusKind::Slow => Some(StatusKind::Haste),
+            _ => None,
+        }
+    }
+
+    // Pedaço do status.png com o ícone
+    pub fn icon_source(self) -> Rect {
+        let index = StatusKind::ALL.iter().position(|&kind| kind == self).expect("ALL tem todos os efeitos");
+        Rect::new(index as f32 * ICON_SIZE, 0.0, ICON_SIZE, ICON_SIZE)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct StatusEffect {
+    pub kind: StatusKind,
+    pub turns: u32,    // Turnos que faltam
+    #[serde(default)]
+    pub potency: i32, // Vida por turno (regeneração, veneno). Os outros ignoram
+}
+
+impl StatusEffect {
+    pub const fn new(kind: StatusKind, turns: u32, potency: i32) -> Self {
+        StatusEffect { kind, turns, potency }
+    }
+}
+
+// O que um turno de efeitos fez com o ator
+pub struct StatusTick {
+    pub hp: i32,                  // Regeneração menos veneno
+    pub expired: Vec<StatusKind>, // Acabaram nesse turno
+}
+
+// Os efeitos de um ator, no máximo um de cada tipo
+#[derive(Debug, Clone, Default)]
+pub struct Statuses {
+    effects: Vec<StatusEffect>,
+}
+
+impl Statuses {
+    pub fn effects(&self) -> &[StatusEffect] {
+        &self.effects
+    }
+
+    pub fn has(&self, kind: StatusKind) -> bool {
+        self.effects.iter().any(|e| e.kind == kind)
+    }
+
+    // Junta com o que já tem do mesmo tipo conforme o Stacking dele
+    pub fn apply(&mut self, effect: StatusEffect) {
+        if let Some(opposite) = effect.kind.opposite() {
+            self.effects.retain(|e| e.kind != opposite);
+        }
+        let Some(current) = self.effects.iter_mut().find(|e| e.kind == effect.kind) else {
+            self.effects.push(effect);
+            return;
+        };
+        match effect.kind.stacking() {
+            Stacking::Refresh => {
+                current.turns = current.turns.max(effect.turns);
+                current.potency = current.potency.max(effect.potency);
+            }
+            Stacking::Extend => current.turns += effect.turns,
+            Stacking::Intensify => {
+                current.turns = current.turns.max(effect.turns);
+                current.potency += effect.potency;
+            }
+        }
+    }
+
+    // Soma na velocidade (NORMAL_SPEED = 10)
+    pub fn speed_bonus(&self) -> i32 {
+        self.effects
+            .iter()
+            .map(|e| match e.kind {
+                StatusKind::Haste => HASTE_BONUS,
+                StatusKind::Slow => -SLOW_PENALTY,
+                _ => 0,
+            })
+            .sum()
+    }
+
+    // Um turno: aplica vida por turno, desconta a duração e tira o que acabou
+    pub fn tick(&mut self) -> StatusTick {
+        let mut hp = 0;
+        for effect in &mut self.effects {
+            match effect.kind {
+                StatusKind::Regeneration => hp += effect.potency,
+                StatusKind::Poison => hp -= effect.potency,
+                _ => {}
+            }
+            effect.turns = effect.turns.saturating_sub(1);
+        }
+        let expired = self.effects.iter().filter(|e| e.turns == 0).map(|e| e.kind).collect();
+        self.effects.retain(|e| e.turns > 0);
+        StatusTick { hp, expired }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn with(effects: &[StatusEffect]) -> Statuses {
+        let mut statuses = Statuses::default();
+        for &effect in effects {
+            statuses.apply(effect);
+        }
+        statuses
+    }
+
+    #[test]
+    fn refresh_keeps_the_bigger_of_each() {
+        let statuses = with(&[
+            StatusEffect::new(StatusKind::Regeneration, 5, 1),
+            StatusEffect::new(StatusKind::Regeneration, 3, 2),
+        ]);
+        assert_eq!(statuses.effects(), [StatusEffect::new(StatusKind::Regeneration, 5, 2)]);
+    }
+
+    #[test]
+    fn extend_adds_the_turns() {
+        let statuses = with(&[
+            StatusEffect::new(StatusKind::Confusion, 4, 0),
+            StatusEffect::new(StatusKind::Confusion, 3, 0),
+        ]);
+        assert_eq!(statuses.effects(), [StatusEffect::new(StatusKind::Confusion, 7, 0)]);
+    }
+
+    #[test]
+    fn intensify_adds_the_potency() {
+        let statuses = with(&[
+            StatusEffect::new(StatusKind::Poison, 3, 1),
+            StatusEffect::new(StatusKind::Poison, 5, 2),
+            StatusEffect::new(StatusKind::Poison, 2, 1),
+        ]);
+        assert_eq!(statuses.effects(), [StatusEffect::new(StatusKind::Poison, 5, 4)]);
+    }
+
+    #[test]
+    fn haste_and_slow_cancel_out() {
+        let mut statuses = with(&[StatusEffect::new(StatusKind::Haste, 5, 0)]);
+        assert_eq!(statuses.speed_bonus(), HASTE_BONUS);
+        statuses.apply(StatusEffect::new(StatusKind::Slow, 3, 0));
+        assert!(!statuses.has(StatusKind::Haste));
+        assert_eq!(statuses.speed_bonus(), -SLOW_PENALTY);
+        statuses.apply(StatusEffect::new(StatusKind::Haste, 2, 0));
+        assert_eq!(statuses.effects(), [StatusEffect::new(StatusKind::Haste, 2, 0)]);
+    }
+
+    #[test]
+    fn tick_heals_hurts_and_expires() {
+        let mut statuses = with(&[
+            StatusEffect::new(StatusKind::Regeneration, 2, 3),
+            StatusEffect::new(StatusKind::Poison, 1, 1),
+            StatusEffect::new(StatusKind::Blindness, 3, 0),
+        ]);
+        let tick = statuses.tick();
+        assert_eq!((tick.hp, tick.expired), (2, vec![StatusKind::Poison]));
+        let tick = statuses.tick();
+        assert_eq!((tick.hp, tick.expired), (3, vec![StatusKind::Regeneration]));
+        let tick = statuses.tick();
+        assert_eq!((tick.hp, tick.expired), (0, vec![StatusKind::Blindness]));
+        assert!(statuses.effects().is_empty());
+        assert!(statuses.tick().expired.is_empty());
+    }
+}
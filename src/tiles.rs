@@ -0,0 +1,263 @@
+use macroquad::prelude::*;
+use std::collections::HashMap;
+use std::fmt;
+
+use crate::TILE_SIZE;
+
+// --- TILES ---
+// Tabela de tipos de tile carregada de assets/tiles.cfg.
+// Uma linha por glifo:
+//
+//   # glifo  coluna  linha  andavel  bloqueia_visao  flags
+//   x        0       0      false    true
+//   s        1       0      true     false           spawn
+//
+// coluna/linha são em tiles dentro do tileset (multiplicadas por TILE_SIZE).
+// flags é opcional, separadas por vírgula: spawn, door, stairs_down, stairs_up, monster.
+// O espaço (' ') é sempre vazio/fora do mapa e não entra na tabela.
+//
+// Uma linha especial diz se dá pra andar na diagonal passando por quinas:
+//
+//   corner_rule  no_squeeze
+//
+// free: sempre pode. no_squeeze: não passa entre duas paredes (padrão).
+// no_cut: não passa rente a nenhuma parede.
+pub const VOID_TILE: char = ' ';
+
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum CornerRule {
+    Free,      // Diagonal sempre liberada se o destino for andável
+    #[default]
+    NoSqueeze, // Bloqueada se as duas quinas forem parede
+    NoCut,     // Bloqueada se qualquer quina for parede
+}
+
+impl CornerRule {
+    fn parse(s: &str) -> Option<Self> {
+        match s {
+            "free" => Some(CornerRule::Free),
+            "no_squeeze" => Some(CornerRule::NoSqueeze),
+            "no_cut" => Some(CornerRule::NoCut),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TileFlag {
+    Spawn,      // Ponto de spawn do jogador
+    Door,       // Porta
+    StairsDown, // Escada pra descer um andar
+    StairsUp,   // Escada pra subir um andar
+    Monster,    // Marcador de monstro (vira chão quando o andar é criado)
+}
+
+impl TileFlag {
+    fn parse(s: &str) -> Option<Self> {
+        match s {
+            "spawn" => Some(TileFlag::Spawn),
+            "door" => Some(TileFlag::Door),
+            "stairs_down" => Some(TileFlag::StairsDown),
+            "stairs_up" => Some(TileFlag::StairsUp),
+            "monster" => Some(TileFlag::Monster),
+            _ => None,
+        }
+    }
+}
+
+pub struct TileDef {
+    pub source: Rect,    // Pedaço do tileset que desenha esse tile
+    pub walkable: bool,  // Dá pra andar em cima?
+    pub opaque: bool,    // Bloqueia visão?
+    pub flags: Vec<TileFlag>,
+}
+
+impl TileDef {
+    pub fn has_flag(&self, flag: TileFlag) -> bool {
+        self.flags.contains(&flag)
+    }
+}
+
+// --- Erros ---
+#[derive(Debug)]
+pub enum TileError {
+    Io { path: String, source: macroquad::Error },
+    Syntax { line: usize, message: String }, // Linha mal formada
+    DuplicateGlyph { line: usize, glyph: char },
+}
+
+impl fmt::Display for TileError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TileError::Io { path, source } => write!(f, "não foi possível ler '{}': {}", path, source),
+            TileError::Syntax { line, message } => write!(f, "linha {}: {}", line, message),
+            TileError::DuplicateGlyph { line, glyph } => write!(f, "linha {}: glifo '{}' definido duas vezes", line, glyph),
+        }
+    }
+}
+
+impl std::error::Error for TileError {}
+
+// --- Registry ---
+pub struct TileRegistry {
+    defs: HashMap<char, TileDef>,
+    corner_rule: CornerRule,
+}
+
+impl TileRegistry {
+    pub async fn load(path: &str) -> Result<Self, TileError> {
+        let text = load_string(path).await.map_err(|source| TileError::Io {
+            path: path.to_owned(),
+            source,
+        })?;
+        Self::parse(&text)
+    }
+
+    pub fn parse(text: &str) -> Result<Self, TileError> {
+        let mut defs = HashMap::new();
+        let mut corner_rule = CornerRule::default();
+
+        for (idx, line) in text.lines().enumerate() {
+            let line_no = idx + 1; // Número de linha "humano" pras mensagens
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let parts: Vec<&str> = line.split_whitespace().collect();
+            if let ["corner_rule", value] = parts[..] {
+                corner_rule = CornerRule::parse(value)
+                    .ok_or_else(|| syntax(line_no, &format!("corner_rule deve ser free, no_squeeze ou no_cut: '{}'", value)))?;
+                continue;
+            }
+            if parts.len() < 5 || parts.len() > 6 {
+                return Err(syntax(line_no, "esperava: glifo coluna linha andavel bloqueia_visao [flags]"));
+            }
+
+            let mut glyph_chars = parts[0].chars();
+            let glyph = match (glyph_chars.next(), glyph_chars.next()) {
+                (Some(c), None) => c,
+                _ => return Err(syntax(line_no, &format!("glifo deve ser um único caractere: '{}'", parts[0]))),
+            };
+
+            let col: u32 = parts[1].parse().map_err(|_| syntax(line_no, &format!("coluna inválida: '{}'", parts[1])))?;
+            let row: u32 = parts[2].parse().map_err(|_| syntax(line_no, &format!("linha inválida: '{}'", parts[2])))?;
+            let walkable = parse_bool(parts[3]).ok_or_else(|| syntax(line_no, &format!("andavel deve ser true/false: '{}'", parts[3])))?;
+            let opaque = parse_bool(parts[4]).ok_or_else(|| syntax(line_no, &format!("bloqueia_visao deve ser true/false: '{}'", parts[4])))?;
+
+            let mut flags = Vec::new();
+            if let Some(flag_list) = parts.get(5) {
+                for name in flag_list.split(',') {
+                    let flag = TileFlag::parse(name).ok_or_else(|| syntax(line_no, &format!("flag desconhecida: '{}'", name)))?;
+                    flags.push(flag);
+                }
+            }
+
+            let def = TileDef {
+                source: Rect::new(col as f32 * TILE_SIZE, row as f32 * TILE_SIZE, TILE_SIZE, TILE_SIZE),
+                walkable,
+                opaque,
+                flags,
+            };
+            if defs.insert(glyph, def).is_some() {
+                return Err(TileError::DuplicateGlyph { line: line_no, glyph });
+            }
+        }
+
+        Ok(TileRegistry { defs, corner_rule })
+    }
+
+    // Definição do glifo. None pra vazio ou glifo desconhecido.
+    pub fn get(&self, glyph: char) -> Option<&TileDef> {
+        self.defs.get(&glyph)
+    }
+
+    pub fn contains(&self, glyph: char) -> bool {
+        glyph == VOID_TILE || self.defs.contains_key(&glyph)
+    }
+
+    // Vazio e glifos desconhecidos nunca são andáveis.
+    pub fn is_walkable(&self, glyph: char) -> bool {
+        self.get(glyph).is_some_and(|def| def.walkable)
+    }
+
+    // Vazio e glifos desconhecidos bloqueiam a visão (é a borda do mundo).
+    pub fn is_opaque(&self, glyph: char) -> bool {
+        self.get(glyph).is_none_or(|def| def.opaque)
+    }
+
+    pub fn has_flag(&self, glyph: char, flag: TileFlag) -> bool {
+        self.get(glyph).is_some_and(|def| def.has_flag(flag))
+    }
+
+    pub fn corner_rule(&self) -> CornerRule {
+        self.corner_rule
+    }
+
+    pub fn len(&self) -> usize {
+        self.defs.len()
+    }
+
+    pub fn opaque_count(&self) -> usize {
+        self.defs.values().filter(|def| def.opaque).count()
+    }
+}
+
+fn parse_bool(s: &str) -> Option<bool> {
+    match s {
+        "true" => Some(true),
+        "false" => Some(false),
+        _ => None,
+    }
+}
+
+fn syntax(line: usize, message: &str) -> TileError {
+    TileError::Syntax { line, message: message.to_owned() }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_glyphs_and_flags() {
+        let registry = TileRegistry::parse("# comentário\n\nx 0 0 false true\nd 2 1 true true door,stairs_down\n").unwrap();
+        assert_eq!(registry.len(), 2);
+        assert_eq!(registry.opaque_count(), 2);
+
+        let door = registry.get('d').unwrap();
+        assert_eq!(door.source, Rect::new(2.0 * TILE_SIZE, TILE_SIZE, TILE_SIZE, TILE_SIZE));
+        assert!(door.walkable && door.has_flag(TileFlag::Door) && door.has_flag(TileFlag::StairsDown));
+        assert!(!registry.is_walkable('x'));
+
+        // Vazio é sempre conhecido, mas nunca andável
+        assert!(registry.contains(VOID_TILE) && !registry.is_walkable(VOID_TILE));
+        assert!(!registry.contains('?'));
+
+        let shipped = std::fs::read_to_string("assets/tiles.cfg").unwrap();
+        assert!(TileRegistry::parse(&shipped).is_ok());
+    }
+
+    #[test]
+    fn duplicate_glyph() {
+        let error = TileRegistry::parse("x 0 0 false true\no 1 0 true false\nx 2 0 true false").err().unwrap();
+        assert!(matches!(error, TileError::DuplicateGlyph { line: 3, glyph: 'x' }), "{}", error);
+    }
+
+    #[test]
+    fn bad_lines_are_syntax_errors() {
+        let cases = [
+            "x 0 0 false",              // Falta coluna
+            "x 0 0 false true spawn 1", // Coluna a mais
+            "xx 0 0 false true",        // Glifo com dois caracteres
+            "x -1 0 false true",        // Coluna negativa
+            "x 0 um false true",        // Linha não numérica
+            "x 0 0 sim true",           // Bool inválido
+            "x 0 0 false true spawn,lava",
+        ];
+        for text in cases {
+            let error = TileRegistry::parse(&format!("o 1 0 true false\n{}", text)).err();
+            assert!(matches!(error, Some(TileError::Syntax { line: 2, .. })), "passou: {}", text);
+        }
+    }
+}
@@ -0,0 +1,81 @@
+use crate::equipment::EquipSlot;
+
+// --- TURNOS ---
+// Sistema de energia: a cada tick do relógio todo ator ganha energia igual à
+// sua velocidade e só pode agir com pelo menos ACTION_COST acumulado.
+// Agir gasta energia. Velocidade 10 = normal (age a cada 10 ticks),
+// 20 = rápido (duas ações por turno), 5 = lento (uma a cada dois turnos).
+pub const ACTION_COST: i32 = 100;
+pub const NORMAL_SPEED: i32 = 10;
+const TICKS_PER_TURN: u64 = (ACTION_COST / NORMAL_SPEED) as u64;
+
+// Ação que o jogador decidiu fazer. Só vira turno quando é resolvida.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Command {
+    Move { dx: isize, dy: isize }, // Anda um tile
+    Wait,                          // Passa o turno parado
+    Pickup,                        // Pega o item de cima do tile
+    Use { index: usize },          // Usa um item da linha index do inventário
+    Drop { index: usize },         // Larga a linha index inteira no tile
+    Equip { index: usize },        // Equipa o item da linha index (troca com o que estava no slot)
+    Unequip { slot: EquipSlot },   // Tira o item do slot e guarda no inventário
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Energy {
+    pub value: i32,
+    pub speed: i32,
+}
+
+impl Energy {
+    // Começa pronto pra agir
+    pub fn new(speed: i32) -> Self {
+        Energy { value: ACTION_COST, speed }
+    }
+
+    pub fn is_ready(&self) -> bool {
+        self.value >= ACTION_COST
+    }
+
+    pub fn gain(&mut self) {
+        self.value += self.speed;
+    }
+
+    pub fn spend(&mut self, cost: i32) {
+        self.value -= cost;
+    }
+}
+
+// Relógio do jogo. O tempo só anda quando o jogador age.
+#[derive(Debug, Default)]
+pub struct Scheduler {
+    time: u64, // Ticks desde o começo do jogo
+}
+
+impl Scheduler {
+    // Relógio parado no tick time (save carregado)
+    pub fn at(time: u64) -> Self {
+        Scheduler { time }
+    }
+
+    pub fn time(&self) -> u64 {
+        self.time
+    }
+
+    // Avança o relógio tick a tick até o jogador ter energia pra agir de novo.
+    // on_tick roda a cada tick, depois do jogador ganhar energia: é onde os
+    // outros atores (monstros) ganham a deles e agem. O parâmetro diz se esse
+    // tick começou um turno novo (é quando os efeitos de status andam).
+    pub fn advance(&mut self, player: &mut Energy, mut on_tick: impl FnMut(bool)) {
+        while !player.is_ready() {
+            self.time += 1;
+            player.gain();
+            on_tick(self.time.is_multiple_of(TICKS_PER_TURN));
+        }
+    }
+
+    // Tempo em turnos "normais" (de um ator com NORMAL_SPEED)
+    pub fn turn(&self) -> u64 {
+        self.time / TICKS_PER_TURN
+    }
+}